@@ -3,13 +3,32 @@ use crate::base::commands::{handler::CommandHandler, Command};
 use crate::base::states::manager::StateManager;
 use crate::base::states::states::State;
 use crate::base::stores::MainStore;
-use crate::base::web::client::WebClient;
+use crate::base::logs::{Log, LogType};
+use crate::base::os::browser;
+use crate::base::web::client::{RetryAttempt, WebClient};
+use crate::base::web::graphql::parser::parse_document;
+use crate::base::web::graphql::schema::{
+    GraphQLSchema, INTROSPECTION_OPERATION, INTROSPECTION_QUERY,
+};
+use crate::base::web::graphql::GraphQLRequest;
+use crate::base::web::grpc::{self, GrpcTarget};
+use crate::base::web::options::RequestOptions;
 use crate::base::web::repository::reqwest::ReqwestClientRepository;
-use crate::base::web::response::Response;
+use crate::base::web::repository::tonic::TonicClientRepository;
+use crate::base::web::repository::tungstenite::TungsteniteClientRepository;
+use crate::base::web::repository::{
+    GrpcClientRepository, HttpError, PreparedRequest, SocketClientRepository,
+};
+use crate::base::web::request::RequestKind;
+use crate::base::web::response::{Response, ResponseStage};
+use crate::base::web::sse::ServerSentEvent;
+use crate::base::web::websocket::{WebSocketEvent, WebSocketMessage};
 use crate::config::configurations::save_files::SaveFiles;
 use crate::input::buffer::InputKeyboardBuffer;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::mpsc::Sender;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
 
 #[derive(Copy, Clone, Debug)]
 pub enum InputMode {
@@ -38,6 +57,13 @@ pub struct App {
 
     // Web Client
     pub client_web: Option<Arc<WebClient<ReqwestClientRepository>>>,
+    pub client_socket: Option<Arc<TungsteniteClientRepository>>,
+    pub client_grpc: Option<Arc<TonicClientRepository>>,
+
+    // Submit running in background, and a counter of submits done. A task only writes
+    // its response if no other submit was made after it
+    pending_submit: Option<JoinHandle<()>>,
+    submit_counter: Arc<AtomicUsize>,
 }
 
 impl Default for App {
@@ -52,6 +78,10 @@ impl Default for App {
             action_manager: None,
             command_handler: None,
             client_web: None,
+            client_socket: None,
+            client_grpc: None,
+            pending_submit: None,
+            submit_counter: Arc::new(AtomicUsize::new(0)),
         }
     }
 }
@@ -76,6 +106,12 @@ impl App {
     pub fn set_web_client(&mut self, client: WebClient<ReqwestClientRepository>) -> () {
         self.client_web = Some(Arc::new(client))
     }
+    pub fn set_socket_client(&mut self, client: TungsteniteClientRepository) -> () {
+        self.client_socket = Some(Arc::new(client))
+    }
+    pub fn set_grpc_client(&mut self, client: TonicClientRepository) -> () {
+        self.client_grpc = Some(Arc::new(client))
+    }
     pub fn set_renderer(&mut self, renderer: Sender<Actions>) -> () {
         self.renderer = Some(renderer)
     }
@@ -142,26 +178,362 @@ impl App {
     }
 
     // Web client ---------------------
-    pub fn dispatch_submit(&self) -> () {
+    pub fn dispatch_submit(&mut self) -> () {
+        match self.get_data_store().get_request().kind {
+            RequestKind::WebSocket => return self.dispatch_websocket(),
+            RequestKind::Grpc => return self.dispatch_grpc(),
+            // The parser may not know all the syntax, so the server has the last word
+            RequestKind::GraphQL => {
+                if let Err(e) = self.validate_graphql_query() {
+                    self.get_data_store_mut()
+                        .set_log_warning(String::from("INVALID QUERY"), e);
+                }
+            }
+            RequestKind::Http => {}
+        }
+
+        // A new submit replaces the one still running
+        if let Some(task) = self.pending_submit.take() {
+            task.abort();
+        }
+
         let client = self.client_web.as_ref().unwrap().clone();
         let request = self.data_store.as_ref().unwrap().get_request().clone();
         let response_data_store = self.data_store.as_ref().unwrap().get_response().clone();
+        let error_data_store = self.get_data_store().get_error();
+
+        let renderer = self.renderer.as_ref().unwrap().clone();
+        let log = self.get_data_store().log.clone();
+
+        let submit_counter = self.submit_counter.clone();
+        let submit_id = submit_counter.fetch_add(1, Ordering::SeqCst) + 1;
+
+        *response_data_store.lock().unwrap() = Response::default_waiting();
 
-        let data_store = self.get_data_store().clone();
+        let task = tokio::task::spawn(async move {
+            // Events of a stream are shown while it's still open
+            let response_of_events = response_data_store.clone();
+            let counter_of_events = submit_counter.clone();
+            let renderer_of_events = renderer.clone();
+            let on_event = move |event: ServerSentEvent| {
+                let mut data = response_of_events.lock().unwrap();
+                if counter_of_events.load(Ordering::SeqCst) != submit_id {
+                    return;
+                }
 
+                data.stage = ResponseStage::Streaming;
+                data.events.push(event);
+                renderer_of_events.send(Actions::Null).unwrap_or(());
+            };
+
+            let on_authorize = Self::create_authorize_listener(log.clone(), renderer.clone());
+
+            let renderer_of_retries = renderer.clone();
+            let on_retry = move |retry: RetryAttempt| {
+                *log.lock().unwrap() = Log::default()
+                    .with_type(LogType::Warning)
+                    .with_title(format!("RETRY {}/{}", retry.attempt, retry.max_retries))
+                    .with_detail(format!(
+                        "{} (waiting {}ms)",
+                        retry.reason,
+                        retry.wait.as_millis()
+                    ));
+                renderer_of_retries.send(Actions::Null).unwrap_or(());
+            };
+
+            // Tokens of OAuth2 are got or refreshed before the request is sent
+            let new_response = match client.authorize((*request).clone(), on_authorize).await {
+                Ok(request) => client.submit(request, on_retry, on_event).await,
+                Err(e) => Err(HttpError::Auth(e)),
+            };
+
+            let mut data = response_data_store.lock().unwrap();
+
+            if submit_counter.load(Ordering::SeqCst) != submit_id {
+                return;
+            }
+
+            Self::set_submit_result(&mut data, &error_data_store, new_response);
+            renderer.send(Actions::Null).unwrap();
+        });
+
+        self.pending_submit = Some(task);
+    }
+
+    // A failed submit leaves an empty Response, with its error kept apart
+    fn set_submit_result(
+        data: &mut Response,
+        error_data_store: &Mutex<Option<HttpError>>,
+        result: Result<Response, HttpError>,
+    ) {
+        let (response, error) = match result {
+            Ok(response) => (response, None),
+            Err(e) => (Response::default_failed(), Some(e)),
+        };
+        *data = response;
+        *error_data_store.lock().unwrap() = error;
+    }
+
+    // The page where the user allows an OAuth2 client is opened in the browser, and
+    // shown in the log when it can't be
+    fn create_authorize_listener(
+        log: Arc<Mutex<Log>>,
+        renderer: Sender<Actions>,
+    ) -> impl Fn(String) + Send + Sync {
+        move |url: String| {
+            let title = match browser::open(&url) {
+                Ok(_) => String::from("OAUTH2 AUTHORIZE IN BROWSER"),
+                Err(_) => String::from("OAUTH2 OPEN TO AUTHORIZE"),
+            };
+            *log.lock().unwrap() = Log::default()
+                .with_type(LogType::Warning)
+                .with_title(title)
+                .with_detail(url);
+            renderer.send(Actions::Null).unwrap_or(());
+        }
+    }
+
+    // The syntax is always checked, the fields only when the schema of URL was fetched
+    fn validate_graphql_query(&self) -> Result<(), String> {
+        let request = self.get_data_store().get_request();
+        let document = parse_document(&request.body)?;
+
+        match self
+            .get_data_store()
+            .graphql_schemas
+            .get_schema(&request.url)
+        {
+            Some(schema) => schema.validate(&document, request.graphql.operation_name.as_deref()),
+            None => Ok(()),
+        }
+    }
+
+    // Fetches the schema of the GraphQL endpoint of current tab, with its headers
+    pub fn dispatch_introspection(&mut self) -> () {
+        let data_store = self.get_data_store();
+        let mut request = (*data_store.get_request()).clone();
+        let url = request.url.clone();
+
+        request.set_body(String::from(INTROSPECTION_QUERY));
+        request.graphql = GraphQLRequest {
+            variables: String::new(),
+            operation_name: Some(String::from(INTROSPECTION_OPERATION)),
+        };
+
+        let schemas = data_store.graphql_schemas.clone();
+        let log = data_store.log.clone();
+        let client = self.client_web.as_ref().unwrap().clone();
         let renderer = self.renderer.as_ref().unwrap().clone();
 
+        self.get_data_store_mut()
+            .set_log_helping(String::from("FETCHING SCHEMA"), url.clone());
+
+        let on_authorize = Self::create_authorize_listener(log.clone(), renderer.clone());
+
         tokio::task::spawn(async move {
-            let new_response = client
-                .submit((*request).clone())
-                .await
-                .map_err(|e| e.to_string());
+            let result = match client.authorize(request, on_authorize).await {
+                Ok(request) => {
+                    client
+                        .submit(request, |_: RetryAttempt| {}, |_: ServerSentEvent| {})
+                        .await
+                }
+                Err(e) => Err(HttpError::Auth(e)),
+            };
+            let result = result
+                .map_err(|e| e.to_string())
+                .and_then(|response| match response.status {
+                    200..=299 => GraphQLSchema::from_introspection(&response.body),
+                    status => Err(format!("Introspection failed with status {}", status)),
+                });
+
+            *log.lock().unwrap() = match result {
+                Ok(schema) => {
+                    let detail = format!("{} types of {}", schema.get_types_count(), url);
+                    schemas.set_schema(url, schema);
+                    Log::default()
+                        .with_type(LogType::Help)
+                        .with_title(String::from("SCHEMA LOADED"))
+                        .with_detail(detail)
+                }
+                Err(e) => Log::default()
+                    .with_type(LogType::Error)
+                    .with_title(String::from("ERROR SCHEMA"))
+                    .with_detail(e),
+            };
+            renderer.send(Actions::Null).unwrap_or(());
+        });
+    }
+
+    // Calls the gRPC method of current tab, it's cancelled as any other submit
+    fn dispatch_grpc(&mut self) -> () {
+        if let Some(task) = self.pending_submit.take() {
+            task.abort();
+        }
+
+        let client = self.client_grpc.as_ref().unwrap().clone();
+        let request_to_do = self.get_grpc_request_to_do();
+        let response_data_store = self.get_data_store().get_response().clone();
+        let error_data_store = self.get_data_store().get_error();
+        let renderer = self.renderer.as_ref().unwrap().clone();
+
+        let submit_counter = self.submit_counter.clone();
+        let submit_id = submit_counter.fetch_add(1, Ordering::SeqCst) + 1;
+
+        *response_data_store.lock().unwrap() = Response::default_waiting();
+
+        let task = tokio::task::spawn(async move {
+            let new_response = client.execute(request_to_do).await;
 
             let mut data = response_data_store.lock().unwrap();
 
-            *data = new_response.unwrap_or_else(|err| Response::default_internal_error(err));
+            if submit_counter.load(Ordering::SeqCst) != submit_id {
+                return;
+            }
+
+            Self::set_submit_result(&mut data, &error_data_store, new_response);
             renderer.send(Actions::Null).unwrap();
         });
+
+        self.pending_submit = Some(task);
+    }
+
+    // Lists the methods of the gRPC server of current tab in the response, and keeps
+    // their names to switch between them
+    pub fn dispatch_grpc_reflection(&mut self) -> () {
+        let request_to_do = self.get_grpc_request_to_do();
+        let endpoint = match GrpcTarget::parse(&request_to_do.url) {
+            Ok(target) => target.endpoint,
+            Err(e) => {
+                return self
+                    .get_data_store_mut()
+                    .set_log_error(String::from("ERROR SCHEMA"), e)
+            }
+        };
+
+        let data_store = self.get_data_store();
+        let methods = data_store.grpc_methods.clone();
+        let log = data_store.log.clone();
+        let response_data_store = data_store.get_response().clone();
+        let client = self.client_grpc.as_ref().unwrap().clone();
+        let renderer = self.renderer.as_ref().unwrap().clone();
+
+        self.get_data_store_mut()
+            .set_log_helping(String::from("FETCHING METHODS"), endpoint.clone());
+
+        tokio::task::spawn(async move {
+            *log.lock().unwrap() = match client.fetch_descriptors(request_to_do).await {
+                Ok(pool) => {
+                    let names = grpc::get_method_names(&pool);
+                    let detail = format!("{} methods of {}", names.len(), endpoint);
+                    methods.set_methods(endpoint, names);
+
+                    let description = grpc::describe_methods(&pool);
+                    *response_data_store.lock().unwrap() = Response {
+                        stage: ResponseStage::Finished,
+                        status: 200,
+                        raw_body: description.as_bytes().to_vec(),
+                        body: description,
+                        ..Response::default()
+                    };
+
+                    Log::default()
+                        .with_type(LogType::Help)
+                        .with_title(String::from("METHODS LOADED"))
+                        .with_detail(detail)
+                }
+                Err(e) => Log::default()
+                    .with_type(LogType::Error)
+                    .with_title(String::from("ERROR SCHEMA"))
+                    .with_detail(e.to_string()),
+            };
+            renderer.send(Actions::Null).unwrap_or(());
+        });
+    }
+
+    fn get_grpc_request_to_do(&self) -> PreparedRequest {
+        let data_store = self.get_data_store();
+        let defaults: RequestOptions = (*data_store.config.request_defaults).clone();
+        let mut request_to_do = PreparedRequest::from((*data_store.get_request()).clone());
+        request_to_do.options = request_to_do.options.with_defaults(&defaults);
+        request_to_do
+    }
+
+    // Connects the WebSocket of current tab or, when it's already open, sends its body
+    fn dispatch_websocket(&mut self) -> () {
+        let request = self.get_data_store().get_request();
+        let uuid = self.get_data_store().get_request_uuid().clone();
+        let data_store = self.data_store.as_mut().unwrap();
+        let session = data_store.websockets.get_session_mut(&uuid);
+
+        if let Some(connection) = session.get_open_connection() {
+            let is_binary = request.options.is_websocket_binary();
+            let result = WebSocketMessage::from_body(&request.body, is_binary)
+                .and_then(|message| connection.send(message));
+
+            if let Err(e) = result {
+                data_store.set_log_error(String::from("ERROR WEBSOCKET"), e);
+            }
+            return;
+        }
+
+        let transcript = session.transcript.clone();
+        transcript.lock().unwrap().start_connecting();
+
+        let renderer = self.renderer.as_ref().unwrap().clone();
+        let on_event = move |event: WebSocketEvent| {
+            transcript.lock().unwrap().apply(event);
+            renderer.send(Actions::Null).unwrap_or(());
+        };
+
+        let defaults: RequestOptions = (*data_store.config.request_defaults).clone();
+        let mut request_to_do = PreparedRequest::from((*request).clone());
+        request_to_do.options = request_to_do.options.with_defaults(&defaults);
+
+        let client = self.client_socket.as_ref().unwrap();
+        let connection = client.connect(request_to_do, Box::new(on_event));
+        session.connection = Some(connection);
+    }
+
+    pub fn close_websocket(&mut self) -> bool {
+        let uuid = self.get_data_store().get_request_uuid().clone();
+        let session = self.get_data_store().websockets.get_session(&uuid);
+
+        match session.and_then(|s| s.get_open_connection()) {
+            Some(connection) => connection.send(WebSocketMessage::Close).is_ok(),
+            None => false,
+        }
+    }
+
+    pub fn is_submit_pending(&self) -> bool {
+        match &self.pending_submit {
+            Some(task) => !task.is_finished(),
+            None => false,
+        }
+    }
+
+    pub fn cancel_submit(&mut self) -> bool {
+        if !self.is_submit_pending() {
+            return false;
+        }
+
+        if let Some(task) = self.pending_submit.take() {
+            task.abort();
+        }
+
+        // Invalidates the response of cancelled task, if it finishes anyway
+        let response_data_store = self.get_data_store().get_response();
+        let mut data = response_data_store.lock().unwrap();
+        self.submit_counter.fetch_add(1, Ordering::SeqCst);
+
+        // A stopped stream keeps the events already received
+        *data = match data.stage {
+            ResponseStage::Streaming => Response {
+                stage: ResponseStage::Cancelled,
+                ..data.clone()
+            },
+            _ => Response::default_cancelled(),
+        };
+        true
     }
 
     // Data store ---------------------
@@ -13,8 +13,12 @@ pub enum Actions {
     Edit,
     Switch,
     Submit,
+    Cancel,
     Undo,
     New,
+    NewWebSocket,
+    NewGraphQL,
+    NewGrpc,
     Delete,
 
     // General Moves
@@ -52,6 +56,11 @@ pub enum Actions {
     UrlEdit,
     MethodEdit,
     ReloadBody,
+    SwitchBodyMode,
+
+    // GraphQL and gRPC
+    Introspect,
+    SwitchOperation,
 
     // 
     GrowHorizontalUiRight,
@@ -0,0 +1,45 @@
+use crate::base::web::cookies::StoredCookie;
+use crate::commands::{Command, Commands};
+use crate::App;
+
+impl Commands {
+    pub fn edit_cookies_vim() -> Command {
+        |app: &mut App| {
+            let cookies = app.get_data_store().config.cookies.get_cookies();
+            let cookies_as_str = serde_json::to_string_pretty(&cookies).unwrap_or_default();
+
+            app.set_vim_mode_with_command(
+                |app: &mut App| {
+                    let buffer = app.get_input_buffer_value();
+                    let data_store = app.get_data_store_mut();
+
+                    let cookies: Vec<StoredCookie> = match serde_json::from_str(&buffer) {
+                        Ok(cookies) => cookies,
+                        Err(e) => {
+                            data_store.set_log_error(String::from("ERROR COOKIES"), e.to_string());
+                            return Ok(());
+                        }
+                    };
+
+                    if let Err(e) = data_store.config.cookies.set_all(cookies) {
+                        data_store.set_log_error(String::from("ERROR SAVE COOKIES"), e);
+                    }
+                    Ok(())
+                },
+                cookies_as_str,
+            );
+            Ok(())
+        }
+    }
+
+    pub fn clear_cookies() -> Command {
+        |app: &mut App| {
+            let data_store = app.get_data_store_mut();
+            match data_store.config.cookies.clear() {
+                Err(e) => data_store.set_log_error(String::from("ERROR SAVE COOKIES"), e),
+                Ok(_) => data_store.set_log_helping(String::from("COOKIES CLEARED"), String::new()),
+            }
+            Ok(())
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::base::states::states::{self, State};
+use crate::base::web::request::RequestKind;
 use crate::commands::{Command, Commands};
 use crate::App;
 
@@ -21,12 +22,58 @@ impl Commands {
             Ok(())
         }
     }
+    // Only GraphQL requests have Variables, the others go straight to the Params
+    pub fn go_to_request_variables_section() -> Command {
+        |app: &mut App| {
+            if app.get_data_store().get_request().kind == RequestKind::GraphQL {
+                app.set_new_state(states::RequestVariablesActiveState::init());
+                Ok(())
+            } else {
+                Self::go_to_request_params_section()(app)
+            }
+        }
+    }
+    // gRPC calls have no query, so they go to the Headers
+    pub fn go_to_request_params_section() -> Command {
+        |app: &mut App| {
+            if app.get_data_store().get_request().kind == RequestKind::Grpc {
+                app.set_new_state(states::RequestHeaderActiveState::init());
+            } else {
+                app.set_new_state(states::RequestParamsActiveState::init());
+            }
+            Ok(())
+        }
+    }
     pub fn go_to_request_header_section() -> Command {
         |app: &mut App| {
             app.set_new_state(states::RequestHeaderActiveState::init());
             Ok(())
         }
     }
+    // Credentials are sent only by HTTP and GraphQL requests, the others go to the Options
+    pub fn go_to_request_auth_section() -> Command {
+        |app: &mut App| {
+            let kind = app.get_data_store().get_request().kind;
+            if matches!(kind, RequestKind::Http | RequestKind::GraphQL) {
+                app.set_new_state(states::RequestAuthActiveState::init());
+            } else {
+                app.set_new_state(states::RequestOptionsActiveState::init());
+            }
+            Ok(())
+        }
+    }
+    pub fn go_to_request_signer_section() -> Command {
+        |app: &mut App| {
+            app.set_new_state(states::RequestSignerActiveState::init());
+            Ok(())
+        }
+    }
+    pub fn go_to_request_options_section() -> Command {
+        |app: &mut App| {
+            app.set_new_state(states::RequestOptionsActiveState::init());
+            Ok(())
+        }
+    }
     pub fn go_to_response_body_section() -> Command {
         |app: &mut App| {
             app.set_new_state(states::ResponseBodyActiveState::init());
@@ -39,6 +86,18 @@ impl Commands {
             Ok(())
         }
     }
+    pub fn go_to_response_cookies_section() -> Command {
+        |app: &mut App| {
+            app.set_new_state(states::ResponseCookiesState::init());
+            Ok(())
+        }
+    }
+    pub fn go_to_response_redirects_section() -> Command {
+        |app: &mut App| {
+            app.set_new_state(states::ResponseRedirectsState::init());
+            Ok(())
+        }
+    }
     pub fn go_to_log_section() -> Command {
         |app: &mut App| {
             app.set_new_state(states::LogsState::init());
@@ -1,3 +1,4 @@
+pub mod cookies;
 pub mod docs;
 pub mod jumps;
 pub mod request;
@@ -1,4 +1,12 @@
-use crate::base::web::request::METHODS;
+use crate::base::web::auth::Auth;
+use crate::base::web::form::FormField;
+use crate::base::web::graphql::parser::parse_document;
+use crate::base::web::grpc::{self, GrpcTarget};
+use crate::base::web::multipart::MultipartPart;
+use crate::base::web::options::RequestOptions;
+use crate::base::web::query::QueryParam;
+use crate::base::web::request::{BodyMode, RequestKind, METHODS};
+use crate::base::web::signing::Signer;
 use crate::commands::{Command, Commands};
 use crate::App;
 use std::collections::HashMap;
@@ -25,6 +33,15 @@ impl Commands {
     }
     pub fn edit_request_body_vim() -> Command {
         |app: &mut App| {
+            let request = app.get_data_store().get_request();
+            match (request.kind, request.body_mode) {
+                (RequestKind::Http, BodyMode::Multipart) => {
+                    return Self::edit_request_multipart_vim()(app)
+                }
+                (RequestKind::Http, BodyMode::Form) => return Self::edit_request_form_vim()(app),
+                _ => {}
+            }
+
             app.set_vim_mode_with_command(
                 |app: &mut App| {
                     let buffer = app.get_input_buffer_value();
@@ -42,6 +59,120 @@ impl Commands {
         }
     }
 
+    // Parts are edited as a JSON list, a new body starts with examples of them
+    pub fn edit_request_multipart_vim() -> Command {
+        |app: &mut App| {
+            let parts = match app.get_data_store().get_request().multipart.clone() {
+                parts if parts.is_empty() => MultipartPart::examples(),
+                parts => parts,
+            };
+            let initial_parts_as_str = serde_json::to_string_pretty(&parts).unwrap_or_default();
+
+            app.set_vim_mode_with_command(
+                |app: &mut App| {
+                    let buffer = app.get_input_buffer_value();
+                    let data_store = app.get_data_store_mut();
+
+                    match serde_json::from_str::<Vec<MultipartPart>>(&buffer) {
+                        Ok(parts) => {
+                            let mut req = (*data_store.get_request()).clone();
+                            req.set_multipart(parts);
+                            data_store.update_request(req);
+                        }
+                        Err(e) => {
+                            data_store.set_log_error(String::from("ERROR MULTIPART"), e.to_string())
+                        }
+                    }
+                    Ok(())
+                },
+                initial_parts_as_str,
+            );
+            Ok(())
+        }
+    }
+
+    // Fields are edited as a JSON list, "enabled": false keeps one without sending it
+    pub fn edit_request_form_vim() -> Command {
+        |app: &mut App| {
+            let fields = match app.get_data_store().get_request().form.clone() {
+                fields if fields.is_empty() => FormField::examples(),
+                fields => fields,
+            };
+            let initial_fields_as_str = serde_json::to_string_pretty(&fields).unwrap_or_default();
+
+            app.set_vim_mode_with_command(
+                |app: &mut App| {
+                    let buffer = app.get_input_buffer_value();
+                    let data_store = app.get_data_store_mut();
+
+                    match serde_json::from_str::<Vec<FormField>>(&buffer) {
+                        Ok(fields) => {
+                            let mut req = (*data_store.get_request()).clone();
+                            req.set_form(fields);
+                            data_store.update_request(req);
+                        }
+                        Err(e) => {
+                            data_store.set_log_error(String::from("ERROR FORM"), e.to_string())
+                        }
+                    }
+                    Ok(())
+                },
+                initial_fields_as_str,
+            );
+            Ok(())
+        }
+    }
+
+    // Params are edited as the fields of a form, the enabled ones are written in the URL
+    pub fn edit_request_params_vim() -> Command {
+        |app: &mut App| {
+            let params = app.get_data_store().get_request().get_params();
+            let initial_params_as_str = serde_json::to_string_pretty(&params).unwrap_or_default();
+
+            app.set_vim_mode_with_command(
+                |app: &mut App| {
+                    let buffer = app.get_input_buffer_value();
+                    let data_store = app.get_data_store_mut();
+
+                    match serde_json::from_str::<Vec<QueryParam>>(&buffer) {
+                        Ok(params) => {
+                            let mut req = (*data_store.get_request()).clone();
+                            req.set_params(params);
+                            data_store.update_request(req);
+                        }
+                        Err(e) => {
+                            data_store.set_log_error(String::from("ERROR PARAMS"), e.to_string())
+                        }
+                    }
+                    Ok(())
+                },
+                initial_params_as_str,
+            );
+            Ok(())
+        }
+    }
+
+    pub fn switch_request_body_mode() -> Command {
+        |app: &mut App| {
+            let data_store = app.get_data_store_mut();
+            let mut req = (*data_store.get_request()).clone();
+
+            if req.kind != RequestKind::Http {
+                data_store.set_log_error(
+                    String::from("ERROR BODY MODE"),
+                    String::from("Only HTTP requests have other modes of body"),
+                );
+                return Ok(());
+            }
+
+            let body_mode = req.body_mode.next();
+            req.set_body_mode(body_mode);
+            data_store.update_request(req);
+            data_store.set_log_helping(String::from("BODY MODE"), body_mode.get_name().to_string());
+            Ok(())
+        }
+    }
+
     pub fn edit_request_headers_vim() -> Command {
         |app: &mut App| {
             let initial_headers = app.get_data_store().get_request().headers.clone();
@@ -76,6 +207,216 @@ impl Commands {
             Ok(())
         }
     }
+    pub fn edit_request_options_vim() -> Command {
+        |app: &mut App| {
+            let initial_options = app.get_data_store().get_request().options.clone();
+            let initial_options_as_str =
+                serde_json::to_string_pretty(&initial_options).unwrap_or_default();
+
+            app.set_vim_mode_with_command(
+                |app: &mut App| {
+                    let buffer = app.get_input_buffer_value();
+                    let data_store = app.get_data_store_mut();
+
+                    let options: RequestOptions = match serde_json::from_str(&buffer) {
+                        Ok(options) => options,
+                        Err(e) => {
+                            data_store.set_log_error(String::from("ERROR OPTIONS"), e.to_string());
+                            return Ok(());
+                        }
+                    };
+
+                    let mut req = (*data_store.get_request()).clone();
+                    req.set_options(options);
+
+                    data_store.update_request(req);
+                    Ok(())
+                },
+                initial_options_as_str,
+            );
+            Ok(())
+        }
+    }
+    pub fn edit_request_auth_vim() -> Command {
+        |app: &mut App| {
+            let initial_auth = app.get_data_store().get_request().auth.clone();
+            let initial_auth_as_str =
+                serde_json::to_string_pretty(&initial_auth).unwrap_or_default();
+
+            app.set_vim_mode_with_command(
+                |app: &mut App| {
+                    let buffer = app.get_input_buffer_value();
+                    let data_store = app.get_data_store_mut();
+
+                    let auth: Auth = match serde_json::from_str(&buffer) {
+                        Ok(auth) => auth,
+                        Err(e) => {
+                            data_store.set_log_error(String::from("ERROR AUTH"), e.to_string());
+                            return Ok(());
+                        }
+                    };
+
+                    let mut req = (*data_store.get_request()).clone();
+                    req.set_auth(auth);
+
+                    data_store.update_request(req);
+                    Ok(())
+                },
+                initial_auth_as_str,
+            );
+            Ok(())
+        }
+    }
+    // An empty buffer or null removes the signer, so the one of config.toml is used
+    pub fn edit_request_signer_vim() -> Command {
+        |app: &mut App| {
+            let initial_signer = app.get_data_store().get_request().signer.clone();
+            let initial_signer_as_str =
+                serde_json::to_string_pretty(&initial_signer).unwrap_or_default();
+
+            app.set_vim_mode_with_command(
+                |app: &mut App| {
+                    let buffer = app.get_input_buffer_value();
+                    let data_store = app.get_data_store_mut();
+
+                    let signer: Option<Signer> = match buffer.trim() {
+                        "" => None,
+                        buffer => match serde_json::from_str(buffer) {
+                            Ok(signer) => signer,
+                            Err(e) => {
+                                data_store
+                                    .set_log_error(String::from("ERROR SIGNER"), e.to_string());
+                                return Ok(());
+                            }
+                        },
+                    };
+
+                    let mut req = (*data_store.get_request()).clone();
+                    req.set_signer(signer);
+
+                    data_store.update_request(req);
+                    Ok(())
+                },
+                initial_signer_as_str,
+            );
+            Ok(())
+        }
+    }
+    pub fn edit_request_variables_vim() -> Command {
+        |app: &mut App| {
+            app.set_vim_mode_with_command(
+                |app: &mut App| {
+                    let buffer = app.get_input_buffer_value();
+                    let data_store = app.get_data_store_mut();
+
+                    let mut req = (*data_store.get_request()).clone();
+                    req.set_graphql_variables(buffer);
+
+                    data_store.update_request(req);
+                    Ok(())
+                },
+                app.get_data_store().get_request().graphql.variables.clone(),
+            );
+            Ok(())
+        }
+    }
+    pub fn switch_operation() -> Command {
+        |app: &mut App| match app.get_data_store().get_request().kind {
+            RequestKind::GraphQL => Self::switch_graphql_operation()(app),
+            RequestKind::Grpc => Self::switch_grpc_method()(app),
+            _ => {
+                app.get_data_store_mut().set_log_error(
+                    String::from("ERROR OPERATION"),
+                    String::from("Only GraphQL and gRPC tabs have operations"),
+                );
+                Ok(())
+            }
+        }
+    }
+    pub fn switch_grpc_method() -> Command {
+        |app: &mut App| {
+            let data_store = app.get_data_store_mut();
+            let mut req = (*data_store.get_request()).clone();
+
+            let target = match GrpcTarget::parse(&req.url) {
+                Ok(target) => target,
+                Err(e) => {
+                    data_store.set_log_error(String::from("ERROR METHOD"), e);
+                    return Ok(());
+                }
+            };
+
+            let names = data_store.grpc_methods.get_methods(&target.endpoint);
+            if names.is_empty() {
+                data_store.set_log_error(
+                    String::from("ERROR METHOD"),
+                    String::from("Methods of server were not fetched, press [i]"),
+                );
+                return Ok(());
+            }
+
+            let next = match target.get_method_name() {
+                Some(current) => match names.iter().position(|name| *name == current) {
+                    Some(i) => (i + 1) % names.len(),
+                    None => 0,
+                },
+                None => 0,
+            };
+
+            match grpc::set_method_of_url(&req.url, &names[next]) {
+                Ok(url) => {
+                    req.set_url(url);
+                    data_store.update_request(req);
+                    data_store.set_log_helping(String::from("METHOD"), names[next].clone());
+                }
+                Err(e) => data_store.set_log_error(String::from("ERROR METHOD"), e),
+            }
+            Ok(())
+        }
+    }
+    pub fn switch_graphql_operation() -> Command {
+        |app: &mut App| {
+            let data_store = app.get_data_store_mut();
+            let mut req = (*data_store.get_request()).clone();
+
+            if req.kind != RequestKind::GraphQL {
+                data_store.set_log_error(
+                    String::from("ERROR OPERATION"),
+                    String::from("Only GraphQL tabs have operations"),
+                );
+                return Ok(());
+            }
+
+            let names = match parse_document(&req.body) {
+                Ok(document) => document.get_operation_names(),
+                Err(e) => {
+                    data_store.set_log_error(String::from("ERROR OPERATION"), e);
+                    return Ok(());
+                }
+            };
+
+            if names.is_empty() {
+                data_store.set_log_error(
+                    String::from("ERROR OPERATION"),
+                    String::from("Query has no named operation"),
+                );
+                return Ok(());
+            }
+
+            let next = match &req.graphql.operation_name {
+                Some(current) => match names.iter().position(|name| name == current) {
+                    Some(i) => (i + 1) % names.len(),
+                    None => 0,
+                },
+                None => 0,
+            };
+
+            req.set_graphql_operation(Some(names[next].clone()));
+            data_store.update_request(req);
+            data_store.set_log_helping(String::from("OPERATION"), names[next].clone());
+            Ok(())
+        }
+    }
     pub fn switch_request_method() -> Command {
         |app: &mut App| {
             let method_stack = [
@@ -85,20 +426,49 @@ impl Commands {
                 METHODS::PATCH,
                 METHODS::DELETE,
                 METHODS::HEAD,
+                METHODS::OPTIONS,
+                METHODS::TRACE,
+                METHODS::CONNECT,
             ];
             let mut new_req = (*app.get_data_store().get_request()).clone();
 
             let current = method_stack
-                .into_iter()
-                .position(|i| i == new_req.method)
+                .iter()
+                .position(|i| *i == new_req.method)
                 .unwrap_or(0);
             let next = (current + 1) % method_stack.len();
 
-            new_req.method = method_stack[next];
+            new_req.method = method_stack[next].clone();
             app.get_data_store_mut().update_request(new_req);
             Ok(())
         }
     }
+    pub fn edit_request_method() -> Command {
+        |app: &mut App| {
+            app.set_input_mode_with_command(
+                |app: &mut App| {
+                    let buffer = app.get_input_buffer_value();
+                    let data_store = app.get_data_store_mut();
+
+                    let method = match METHODS::from_name(&buffer) {
+                        Ok(method) => method,
+                        Err(e) => {
+                            data_store.set_log_error(String::from("ERROR METHOD"), e);
+                            return Ok(());
+                        }
+                    };
+
+                    let mut req = (*data_store.get_request()).clone();
+                    req.set_method(method);
+
+                    data_store.update_request(req);
+                    Ok(())
+                },
+                app.get_data_store().get_request().method.to_string(),
+            );
+            Ok(())
+        }
+    }
     pub fn edit_request_url() -> Command {
         |app: &mut App| {
             app.set_input_mode_with_command(
@@ -126,7 +496,17 @@ impl Commands {
             let buffer = file_handler.get_content(current_uuid)?;
 
             let mut req = (*data_store.get_request()).clone();
-            req.set_body(buffer.clone());
+            match (req.kind, req.body_mode) {
+                (RequestKind::Http, BodyMode::Multipart) => {
+                    let parts = serde_json::from_str(&buffer).map_err(|e| e.to_string())?;
+                    req.set_multipart(parts);
+                }
+                (RequestKind::Http, BodyMode::Form) => {
+                    let fields = serde_json::from_str(&buffer).map_err(|e| e.to_string())?;
+                    req.set_form(fields);
+                }
+                _ => req.set_body(buffer.clone()),
+            }
 
             data_store.update_request(req);
 
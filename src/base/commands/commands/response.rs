@@ -1,5 +1,6 @@
 use crate::commands::{Command, Commands};
 use crate::App;
+use reqwest::Url;
 
 impl Commands {
     pub fn edit_response_vim() -> Command {
@@ -12,4 +13,43 @@ impl Commands {
             Ok(())
         }
     }
+
+    pub fn save_response_body() -> Command {
+        |app: &mut App| {
+            // Name of the file suggested is the last segment of the URL
+            let url = app.get_data_store().get_request().url.clone();
+            let file_name = Url::parse(&url)
+                .ok()
+                .and_then(|url| {
+                    url.path_segments()
+                        .and_then(|mut segments| segments.next_back().map(|s| s.to_string()))
+                })
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| String::from("response"));
+
+            app.set_input_mode_with_command(
+                |app: &mut App| {
+                    let path = app.get_input_buffer_value();
+                    let data_store = app.get_data_store_mut();
+                    let response = data_store.get_response().lock().unwrap().clone();
+
+                    // The bytes of the body, not the text shown. A compressed one is
+                    // saved already decoded, as it's kept only that way
+                    match std::fs::write(&path, &response.raw_body) {
+                        Ok(_) => data_store.set_log_helping(
+                            String::from("RESPONSE SAVED"),
+                            format!("{} bytes in {}", response.raw_body.len(), path),
+                        ),
+                        Err(e) => data_store.set_log_error(
+                            String::from("ERROR SAVE RESPONSE"),
+                            format!("{}: {}", path, e),
+                        ),
+                    }
+                    Ok(())
+                },
+                file_name,
+            );
+            Ok(())
+        }
+    }
 }
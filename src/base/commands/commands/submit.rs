@@ -1,3 +1,5 @@
+use crate::base::web::request::RequestKind;
+use crate::base::web::response::ResponseStage;
 use crate::commands::{Command, Commands};
 use crate::App;
 
@@ -8,4 +10,41 @@ impl Commands {
             Ok(())
         }
     }
+
+    pub fn cancel_submit() -> Command {
+        |app: &mut App| {
+            if app.close_websocket() {
+                app.get_data_store_mut()
+                    .set_log_warning(String::from("WEBSOCKET CLOSING"), String::new());
+                return Ok(());
+            }
+
+            let was_streaming = app.get_data_store().get_response().lock().unwrap().stage
+                == ResponseStage::Streaming;
+
+            if app.cancel_submit() {
+                let title = if was_streaming {
+                    String::from("STREAM STOPPED")
+                } else {
+                    String::from("REQUEST CANCELLED")
+                };
+                app.get_data_store_mut().set_log_warning(title, String::new());
+            }
+            Ok(())
+        }
+    }
+
+    pub fn fetch_schema() -> Command {
+        |app: &mut App| {
+            match app.get_data_store().get_request().kind {
+                RequestKind::GraphQL => app.dispatch_introspection(),
+                RequestKind::Grpc => app.dispatch_grpc_reflection(),
+                _ => app.get_data_store_mut().set_log_error(
+                    String::from("ERROR SCHEMA"),
+                    String::from("Only GraphQL and gRPC tabs have a schema to fetch"),
+                ),
+            }
+            Ok(())
+        }
+    }
 }
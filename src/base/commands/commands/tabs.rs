@@ -25,6 +25,27 @@ impl Commands {
         }
     }
 
+    pub fn add_new_websocket_tab() -> Command {
+        |app: &mut App| {
+            app.get_data_store_mut().add_websocket_request();
+            Ok(())
+        }
+    }
+
+    pub fn add_new_graphql_tab() -> Command {
+        |app: &mut App| {
+            app.get_data_store_mut().add_graphql_request();
+            Ok(())
+        }
+    }
+
+    pub fn add_new_grpc_tab() -> Command {
+        |app: &mut App| {
+            app.get_data_store_mut().add_grpc_request();
+            Ok(())
+        }
+    }
+
     pub fn rename_tab() -> Command {
         |app: &mut App| {
             app.set_input_mode_with_command(
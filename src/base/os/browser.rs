@@ -0,0 +1,21 @@
+use std::process::{Command as OSCommand, Stdio};
+
+#[cfg(target_os = "macos")]
+static OPENER: &[&str] = &["open"];
+#[cfg(target_os = "windows")]
+static OPENER: &[&str] = &["cmd", "/C", "start", ""];
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+static OPENER: &[&str] = &["xdg-open"];
+
+/// Opens `url` in the default browser of system, without waiting for it
+pub fn open(url: &str) -> Result<(), String> {
+    OSCommand::new(OPENER[0])
+        .args(&OPENER[1..])
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("{}: {}", OPENER[0], e))
+}
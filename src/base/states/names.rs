@@ -5,7 +5,14 @@ pub enum StatesNames {
     Url,
     RequestHeaders,
     RequestBody,
+    RequestVariables,
+    RequestParams,
+    RequestAuth,
+    RequestSigner,
+    RequestOptions,
     ResponseHeader,
+    ResponseCookies,
+    ResponseRedirects,
     ResponseBody,
     Log,
     Empty,
@@ -18,12 +18,27 @@ pub trait State {
 mod active_logs;
 pub use active_logs::LogsState;
 
+mod active_request_auth;
+pub use active_request_auth::RequestAuthActiveState;
+
 mod active_request_body;
 pub use active_request_body::RequestActiveState;
 
 mod active_request_headers;
 pub use active_request_headers::RequestHeaderActiveState;
 
+mod active_request_options;
+pub use active_request_options::RequestOptionsActiveState;
+
+mod active_request_variables;
+pub use active_request_variables::RequestVariablesActiveState;
+
+mod active_request_signer;
+pub use active_request_signer::RequestSignerActiveState;
+
+mod active_request_params;
+pub use active_request_params::RequestParamsActiveState;
+
 mod active_request_url;
 pub use active_request_url::RequestUrlActiveState;
 
@@ -33,6 +48,12 @@ pub use active_response_body::ResponseBodyActiveState;
 mod active_response_headers;
 pub use active_response_headers::ResponseHeadersState;
 
+mod active_response_cookies;
+pub use active_response_cookies::ResponseCookiesState;
+
+mod active_response_redirects;
+pub use active_response_redirects::ResponseRedirectsState;
+
 mod active_tablist;
 pub use active_tablist::TabActiveState;
 
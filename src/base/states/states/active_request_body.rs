@@ -17,7 +17,8 @@ impl State for RequestActiveState {
         Self {
             maps: HashMap::from([
                 (Actions::Edit, Commands::edit_request_body_vim()),
-                (Actions::Switch, Commands::go_to_request_header_section()),
+                (Actions::SwitchBodyMode, Commands::switch_request_body_mode()),
+                (Actions::Switch, Commands::go_to_request_variables_section()),
                 (Actions::Up, Commands::go_to_url_section()),
                 (Actions::Down, Commands::go_to_log_section()),
                 (Actions::Right, Commands::go_to_response_body_section()),
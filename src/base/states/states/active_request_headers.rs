@@ -17,7 +17,7 @@ impl State for RequestHeaderActiveState {
         Self {
             maps: HashMap::from([
                 (Actions::Edit, Commands::edit_request_headers_vim()),
-                (Actions::Switch, Commands::go_to_request_body_section()),
+                (Actions::Switch, Commands::go_to_request_auth_section()),
                 (Actions::Up, Commands::go_to_url_section()),
                 (Actions::Down, Commands::go_to_log_section()),
             ]),
@@ -0,0 +1,27 @@
+use crate::actions::Actions;
+use crate::base::states::states::{CommandsMap, State, StatesNames};
+use crate::commands::Commands;
+use std::collections::HashMap;
+
+pub struct RequestOptionsActiveState {
+    pub maps: CommandsMap,
+}
+impl State for RequestOptionsActiveState {
+    fn get_state_name(&self) -> StatesNames {
+        StatesNames::RequestOptions
+    }
+    fn get_map(&self) -> &CommandsMap {
+        &self.maps
+    }
+    fn init() -> Self {
+        Self {
+            maps: HashMap::from([
+                (Actions::Edit, Commands::edit_request_options_vim()),
+                (Actions::Switch, Commands::go_to_request_body_section()),
+                (Actions::Up, Commands::go_to_url_section()),
+                (Actions::Down, Commands::go_to_log_section()),
+                (Actions::Right, Commands::go_to_response_body_section()),
+            ]),
+        }
+    }
+}
@@ -21,7 +21,11 @@ impl State for RequestUrlActiveState {
                 (Actions::Right, Commands::go_to_response_body_section()),
                 (Actions::Edit, Commands::edit_request_url()),
                 (Actions::New, Commands::add_new_tab()),
+                (Actions::NewWebSocket, Commands::add_new_websocket_tab()),
+                (Actions::NewGraphQL, Commands::add_new_graphql_tab()),
+                (Actions::NewGrpc, Commands::add_new_grpc_tab()),
                 (Actions::Switch, Commands::switch_request_method()),
+                (Actions::MethodEdit, Commands::edit_request_method()),
             ]),
         }
     }
@@ -18,6 +18,7 @@ impl State for ResponseBodyActiveState {
             maps: HashMap::from([
                 (Actions::Edit, Commands::edit_response_vim()),
                 (Actions::Switch, Commands::go_to_response_headers_section()),
+                (Actions::Save, Commands::save_response_body()),
                 (Actions::Left, Commands::go_to_request_body_section()),
                 (Actions::Up, Commands::go_to_tab_section()),
                 (Actions::Down, Commands::go_to_log_section()),
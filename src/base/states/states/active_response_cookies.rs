@@ -0,0 +1,28 @@
+use crate::actions::Actions;
+use crate::base::states::states::{CommandsMap, State, StatesNames};
+use crate::commands::Commands;
+use std::collections::HashMap;
+
+pub struct ResponseCookiesState {
+    pub maps: CommandsMap,
+}
+impl State for ResponseCookiesState {
+    fn get_state_name(&self) -> StatesNames {
+        StatesNames::ResponseCookies
+    }
+    fn get_map(&self) -> &CommandsMap {
+        &self.maps
+    }
+    fn init() -> Self {
+        Self {
+            maps: HashMap::from([
+                (Actions::Edit, Commands::edit_cookies_vim()),
+                (Actions::Delete, Commands::clear_cookies()),
+                (Actions::Switch, Commands::go_to_response_redirects_section()),
+                (Actions::Left, Commands::go_to_request_body_section()),
+                (Actions::Up, Commands::go_to_tab_section()),
+                (Actions::Down, Commands::go_to_log_section()),
+            ]),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use crate::actions::Actions;
+use crate::base::states::states::{CommandsMap, State, StatesNames};
+use crate::commands::Commands;
+use std::collections::HashMap;
+
+pub struct ResponseRedirectsState {
+    pub maps: CommandsMap,
+}
+impl State for ResponseRedirectsState {
+    fn get_state_name(&self) -> StatesNames {
+        StatesNames::ResponseRedirects
+    }
+    fn get_map(&self) -> &CommandsMap {
+        &self.maps
+    }
+    fn init() -> Self {
+        Self {
+            maps: HashMap::from([
+                (Actions::Switch, Commands::go_to_response_body_section()),
+                (Actions::Left, Commands::go_to_request_body_section()),
+                (Actions::Up, Commands::go_to_tab_section()),
+                (Actions::Down, Commands::go_to_log_section()),
+            ]),
+        }
+    }
+}
@@ -19,6 +19,9 @@ impl State for TabActiveState {
                 (Actions::Edit, Commands::rename_tab()),
                 (Actions::Switch, Commands::go_to_next_tab()),
                 (Actions::New, Commands::add_new_tab()),
+                (Actions::NewWebSocket, Commands::add_new_websocket_tab()),
+                (Actions::NewGraphQL, Commands::add_new_graphql_tab()),
+                (Actions::NewGrpc, Commands::add_new_grpc_tab()),
                 (Actions::Up, Commands::do_nothing()),
                 (Actions::Down, Commands::go_to_url_section()),
                 (Actions::Delete, Commands::delete_tab()),
@@ -37,12 +37,15 @@ impl State for DefaultState {
                 (Actions::RenameTab, Commands::rename_tab()),
                 (Actions::DeleteTab, Commands::delete_tab()),
                 (Actions::Submit, Commands::submit()),
+                (Actions::Cancel, Commands::cancel_submit()),
                 (Actions::Quit, Commands::quit()),
                 (Actions::AskForHelp, Commands::open_help_screen()),
                 (Actions::Save, Commands::save_request()),
                 (Actions::GrowHorizontalUiLeft, Commands::grow_left_ui()),
                 (Actions::GrowHorizontalUiRight, Commands::grow_right_ui()),
                 (Actions::ReloadBody, Commands::restart_body_of_file()),
+                (Actions::Introspect, Commands::fetch_schema()),
+                (Actions::SwitchOperation, Commands::switch_operation()),
             ]),
         }
     }
@@ -0,0 +1,21 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::base::web::graphql::schema::GraphQLSchema;
+
+// Schemas fetched by introspection, by URL of their endpoint. Shared with the task
+// fetching them, they are not saved in files
+#[derive(Clone, Default)]
+pub struct GraphQLSchemaStore {
+    schemas: Arc<Mutex<HashMap<String, GraphQLSchema>>>,
+}
+
+impl GraphQLSchemaStore {
+    pub fn get_schema(&self, url: &str) -> Option<GraphQLSchema> {
+        self.schemas.lock().unwrap().get(url).cloned()
+    }
+
+    pub fn set_schema(&self, url: String, schema: GraphQLSchema) {
+        self.schemas.lock().unwrap().insert(url, schema);
+    }
+}
@@ -0,0 +1,24 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+// Methods of each gRPC server, by its endpoint, as "package.Service/Method". Shared with
+// the task fetching them, they are not saved in files
+#[derive(Clone, Default)]
+pub struct GrpcMethodsStore {
+    methods: Arc<Mutex<HashMap<String, Vec<String>>>>,
+}
+
+impl GrpcMethodsStore {
+    pub fn get_methods(&self, endpoint: &str) -> Vec<String> {
+        self.methods
+            .lock()
+            .unwrap()
+            .get(endpoint)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn set_methods(&self, endpoint: String, methods: Vec<String>) {
+        self.methods.lock().unwrap().insert(endpoint, methods);
+    }
+}
@@ -1,18 +1,30 @@
+use reqwest::Url;
 use std::sync::Arc;
 
+pub mod graphql;
+pub mod grpc;
 pub mod requests;
+pub mod websockets;
 
 use crate::base::states::names::StatesNames;
 use crate::input::buffer::InputKeyboardBuffer;
 use crate::utils::custom_types::uuid::UUID;
 use crate::{app::InputMode, config::manager::ConfigManager};
 
+use self::graphql::GraphQLSchemaStore;
+use self::grpc::GrpcMethodsStore;
 use self::requests::RequestStore;
+use self::websockets::WebSocketStore;
 
 use super::{
     doc::handler::DocReaderHandler,
     logs::LogType,
-    web::{request::Request, response::Response},
+    web::{
+        proxy,
+        repository::HttpError,
+        request::{Request, RequestKind},
+        response::Response,
+    },
 };
 
 use std::sync::Mutex;
@@ -24,6 +36,13 @@ pub struct MainStore {
     // Web
     requests: RequestStore,
     last_response: Arc<Mutex<Response>>,
+    // Why the last Response failed, set with its stage as Failed
+    last_error: Arc<Mutex<Option<HttpError>>>,
+    pub websockets: WebSocketStore,
+    pub graphql_schemas: GraphQLSchemaStore,
+    pub grpc_methods: GrpcMethodsStore,
+    // Proxy of the current request, resolved when it changes and not on each frame
+    active_proxy: Option<String>,
 
     // States
     pub current_state: StatesNames,
@@ -32,8 +51,8 @@ pub struct MainStore {
     pub mode: InputMode,
     pub input_buffer: InputKeyboardBuffer,
 
-    // Logs
-    pub log: Log,
+    // Logs, shared to be written by tasks running in background
+    pub log: Arc<Mutex<Log>>,
 
     // DocReader
     pub doc_reader: Option<DocReaderHandler>,
@@ -46,16 +65,23 @@ impl MainStore {
     pub fn init(config: ConfigManager) -> Self {
         let last_response = Arc::new(Mutex::new(Response::default()));
 
-        Self {
+        let mut store = Self {
             requests: RequestStore::init(config.saved_requests.clone()),
             last_response,
+            last_error: Arc::new(Mutex::new(None)),
+            websockets: WebSocketStore::default(),
+            graphql_schemas: GraphQLSchemaStore::default(),
+            grpc_methods: GrpcMethodsStore::default(),
+            active_proxy: None,
             current_state: StatesNames::Default,
             mode: InputMode::Normal,
             input_buffer: InputKeyboardBuffer::init(),
-            log: Log::default(),
+            log: Arc::new(Mutex::new(Log::default())),
             doc_reader: None,
             config,
-        }
+        };
+        store.resolve_active_proxy();
+        store
 
         // let requests = RequestStore::init(dd.config.saved_requests);
         // dd.requests = requests;
@@ -63,11 +89,14 @@ impl MainStore {
 
     // Logs
     pub fn set_log(&mut self, log_type: LogType, title: String, detail: String) -> () {
-        self.log = Log::default()
+        *self.log.lock().unwrap() = Log::default()
             .with_type(log_type)
             .with_title(title)
             .with_detail(detail);
     }
+    pub fn get_log(&self) -> Log {
+        self.log.lock().unwrap().clone()
+    }
     pub fn set_log_error(&mut self, title: String, detail: String) -> () {
         self.set_log(LogType::Error, title, detail)
     }
@@ -104,7 +133,23 @@ impl MainStore {
     }
 
     pub fn update_request(&mut self, request: Request) -> () {
-        self.requests.update_request(request)
+        self.requests.update_request(request);
+        self.resolve_active_proxy();
+    }
+
+    /// Proxy the current request goes through, as text without its password
+    pub fn get_active_proxy(&self) -> Option<&str> {
+        self.active_proxy.as_deref()
+    }
+
+    fn resolve_active_proxy(&mut self) {
+        let request = self.requests.get_request();
+        let options = request.options.with_defaults(&self.config.request_defaults);
+        self.active_proxy = Url::parse(&request.url)
+            .ok()
+            .filter(|_| matches!(request.kind, RequestKind::Http | RequestKind::GraphQL))
+            .and_then(|url| proxy::get_proxy_for(&url, &options))
+            .map(|proxy| proxy::get_proxy_as_text(&proxy));
     }
 
     pub fn save_request(&mut self) -> Result<(), String> {
@@ -112,24 +157,52 @@ impl MainStore {
     }
 
     pub fn goto_request(&mut self, index: usize) -> Option<()> {
-        self.requests.goto_request(index)
+        self.requests.goto_request(index)?;
+        self.resolve_active_proxy();
+        Some(())
     }
 
     pub fn goto_next_request(&mut self) -> () {
-        self.requests.goto_next_request()
+        self.requests.goto_next_request();
+        self.resolve_active_proxy();
     }
     pub fn goto_prev_request(&mut self) -> () {
-        self.requests.goto_prev_request()
+        self.requests.goto_prev_request();
+        self.resolve_active_proxy();
     }
 
     pub fn add_request(&mut self) -> usize {
-        self.requests.add_request()
+        let index = self.requests.add_request();
+        self.resolve_active_proxy();
+        index
+    }
+
+    pub fn add_websocket_request(&mut self) -> usize {
+        let index = self.requests.add_websocket_request();
+        self.resolve_active_proxy();
+        index
+    }
+
+    pub fn add_graphql_request(&mut self) -> usize {
+        let index = self.requests.add_graphql_request();
+        self.resolve_active_proxy();
+        index
+    }
+
+    pub fn add_grpc_request(&mut self) -> usize {
+        let index = self.requests.add_grpc_request();
+        self.resolve_active_proxy();
+        index
     }
 
     pub fn delete_current_request(&mut self) -> () {
+        let uuid = self.get_request_uuid().clone();
+        self.websockets.remove_session(&uuid);
+
         if let Err(e) = self.requests.delete_current_request() {
-            self.log.with_type(LogType::Error).with_detail(e);
+            self.set_log_error(String::from("ERROR DELETE REQUEST"), e);
         }
+        self.resolve_active_proxy();
     }
 
     // Response
@@ -137,6 +210,10 @@ impl MainStore {
         self.last_response.clone()
     }
 
+    pub fn get_error(&self) -> Arc<Mutex<Option<HttpError>>> {
+        self.last_error.clone()
+    }
+
     pub fn get_keys_queue(&self) -> String {
         "ai".to_string()
     }
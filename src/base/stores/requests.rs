@@ -50,8 +50,23 @@ impl RequestStore {
     }
 
     pub fn add_request(&mut self) -> usize {
+        self.insert_request(Request::default())
+    }
+
+    pub fn add_websocket_request(&mut self) -> usize {
+        self.insert_request(Request::default_websocket())
+    }
+
+    pub fn add_graphql_request(&mut self) -> usize {
+        self.insert_request(Request::default_graphql())
+    }
+
+    pub fn add_grpc_request(&mut self) -> usize {
+        self.insert_request(Request::default_grpc())
+    }
+
+    fn insert_request(&mut self, req: Request) -> usize {
         let uuid = UUID::new();
-        let req = Request::default();
         self.request_in_memory.insert(uuid.clone(), req);
         self.requests.push(uuid);
 
@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::base::web::websocket::{WebSocketConnection, WebSocketTranscript};
+use crate::utils::custom_types::uuid::UUID;
+
+// Connection of a WebSocket tab and what happened in it. The transcript is shared
+// with the task of the connection, which writes on it
+#[derive(Clone, Default)]
+pub struct WebSocketSession {
+    pub transcript: Arc<Mutex<WebSocketTranscript>>,
+    pub connection: Option<WebSocketConnection>,
+}
+
+impl WebSocketSession {
+    pub fn get_open_connection(&self) -> Option<&WebSocketConnection> {
+        self.connection.as_ref().filter(|c| c.is_open())
+    }
+}
+
+// Sessions of the WebSocket tabs, they are not saved in files
+#[derive(Clone, Default)]
+pub struct WebSocketStore {
+    sessions: HashMap<UUID, WebSocketSession>,
+}
+
+impl WebSocketStore {
+    pub fn get_session(&self, uuid: &UUID) -> Option<&WebSocketSession> {
+        self.sessions.get(uuid)
+    }
+
+    pub fn get_session_mut(&mut self, uuid: &UUID) -> &mut WebSocketSession {
+        self.sessions.entry(uuid.clone()).or_default()
+    }
+
+    /// Transcript of the tab, empty when it was never connected
+    pub fn get_transcript(&self, uuid: &UUID) -> WebSocketTranscript {
+        match self.sessions.get(uuid) {
+            Some(session) => session.transcript.lock().unwrap().clone(),
+            None => WebSocketTranscript::default(),
+        }
+    }
+
+    // Dropping the connection closes it
+    pub fn remove_session(&mut self, uuid: &UUID) {
+        self.sessions.remove(uuid);
+    }
+}
@@ -1,11 +1,12 @@
 use super::{Validator, Validators};
-use crate::base::web::request::Request;
+use crate::base::web::form::{self, FORM_CONTENT_TYPE};
+use crate::base::web::request::{BodyMode, Request, RequestKind, METHODS};
 
 impl Validators {
     pub fn url_protocol_request() -> Validator<Request> {
         |req: &mut Request| {
             let has_the_protocol_in_begin =
-                regex::Regex::new(r"^((http|https)://)(.+)$").map_err(|e| e.to_string())?;
+                regex::Regex::new(r"^((http|https|unix)://)(.+)$").map_err(|e| e.to_string())?;
 
             if !has_the_protocol_in_begin.is_match(&req.url) {
                 let protocol = "http://".to_string();
@@ -15,6 +16,50 @@ impl Validators {
             Ok(())
         }
     }
+
+    // The query and variables of a GraphQL request are sent together, as JSON by POST
+    pub fn graphql_payload_request() -> Validator<Request> {
+        |req: &mut Request| {
+            if req.kind != RequestKind::GraphQL {
+                return Ok(());
+            }
+
+            req.body = req.graphql.to_payload(&req.body)?;
+            req.method = METHODS::POST;
+
+            let has_content_type = req
+                .headers
+                .keys()
+                .any(|key| key.eq_ignore_ascii_case("content-type"));
+            if !has_content_type {
+                req.headers.insert(
+                    String::from("Content-Type"),
+                    String::from("application/json"),
+                );
+            }
+
+            Ok(())
+        }
+    }
+
+    // Fields of a form body are encoded, its Content-Type replaces the one of Request
+    pub fn form_body_request() -> Validator<Request> {
+        |req: &mut Request| {
+            if req.kind != RequestKind::Http || req.body_mode != BodyMode::Form {
+                return Ok(());
+            }
+
+            req.body = form::encode(&req.form);
+            req.headers
+                .retain(|key, _| !key.eq_ignore_ascii_case("content-type"));
+            req.headers.insert(
+                String::from("Content-Type"),
+                String::from(FORM_CONTENT_TYPE),
+            );
+
+            Ok(())
+        }
+    }
 }
 
 #[cfg(test)]
@@ -43,6 +88,19 @@ mod tests {
         assert_eq!(req_with_https.url, req_final.url);
     }
 
+    #[test]
+    fn should_ignore_unix_socket_url() {
+        let req = Request {
+            url: String::from("unix:///var/run/docker.sock:/v1.41/containers/json"),
+            ..Request::default()
+        };
+
+        let req_final = ValidatorsHandler::from(&req)
+            .execute(vec![Validators::url_protocol_request()])
+            .unwrap();
+        assert_eq!(req.url, req_final.url);
+    }
+
     #[test]
     fn should_modify_if_not_protocol() {
         let mut req = Request::default();
@@ -53,4 +111,38 @@ mod tests {
             .unwrap();
         assert_eq!("http://url.com".to_string(), req_final.url);
     }
+
+    #[test]
+    fn should_send_graphql_query_as_json() {
+        let mut req = Request::default_graphql();
+        req.method = METHODS::GET;
+        req.headers.clear();
+        req.body = String::from("{ users { id } }");
+
+        let req_final = ValidatorsHandler::from(&req)
+            .execute(vec![Validators::graphql_payload_request()])
+            .unwrap();
+
+        assert_eq!(req_final.method, METHODS::POST);
+        assert_eq!(req_final.body, r#"{"query":"{ users { id } }"}"#);
+        assert_eq!(req_final.headers["Content-Type"], "application/json");
+    }
+
+    #[test]
+    fn should_send_form_fields_encoded() {
+        let mut req = Request::default();
+        req.set_body_mode(BodyMode::Form);
+        req.set_form(serde_json::from_str(r#"[{ "key": "q", "value": "a b" }]"#).unwrap());
+
+        let req_final = ValidatorsHandler::from(&req)
+            .execute(vec![Validators::form_body_request()])
+            .unwrap();
+
+        assert_eq!(req_final.body, "q=a+b");
+        assert_eq!(req_final.headers.len(), 1);
+        assert_eq!(
+            req_final.headers["Content-Type"],
+            "application/x-www-form-urlencoded"
+        );
+    }
 }
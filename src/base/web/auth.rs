@@ -0,0 +1,494 @@
+use base64::Engine;
+use md5::Md5;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+use super::oauth::OAuth2Config;
+use super::query;
+use super::repository::PreparedRequest;
+use super::response::Response;
+
+// Where an API key is sent
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiKeyLocation {
+    #[default]
+    Header,
+    Query,
+}
+
+// Credentials of a Request, written in the editor as { "type": "basic", ... }. Requests
+// "inherit" the [auth] table of config.toml, "none" sends no credentials even with one
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Auth {
+    #[default]
+    Inherit,
+    None,
+    Basic {
+        username: String,
+        #[serde(default)]
+        password: String,
+    },
+    Bearer {
+        token: String,
+    },
+    ApiKey {
+        key: String,
+        value: String,
+        #[serde(default)]
+        location: ApiKeyLocation,
+    },
+    // Sent only after the server answers with its challenge, in a 401
+    Digest {
+        username: String,
+        #[serde(default)]
+        password: String,
+    },
+    // Its token is got before the request is sent, then it's sent as a Bearer one
+    #[serde(rename = "oauth2")]
+    OAuth2(OAuth2Config),
+}
+
+impl Auth {
+    /// Auth used by a Request, the `default` one when it inherits it
+    pub fn with_default(&self, default: &Auth) -> Auth {
+        match self {
+            Self::Inherit => default.clone(),
+            auth => auth.clone(),
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Inherit => "inherited",
+            Self::None => "none",
+            Self::Basic { .. } => "Basic",
+            Self::Bearer { .. } => "Bearer",
+            Self::ApiKey { .. } => "API key",
+            Self::Digest { .. } => "Digest",
+            Self::OAuth2(_) => "OAuth2",
+        }
+    }
+
+    /// Sets the credentials in `request`, replacing the ones written in its headers
+    pub fn apply(&self, request: &mut PreparedRequest) {
+        match self {
+            Self::Basic { username, password } => {
+                let credentials = base64::engine::general_purpose::STANDARD
+                    .encode(format!("{}:{}", username, password));
+                set_header(request, "Authorization", format!("Basic {}", credentials));
+            }
+            Self::Bearer { token } => {
+                set_header(request, "Authorization", format!("Bearer {}", token));
+            }
+            Self::ApiKey {
+                key,
+                value,
+                location: ApiKeyLocation::Header,
+            } => set_header(request, key, value.clone()),
+            Self::ApiKey {
+                key,
+                value,
+                location: ApiKeyLocation::Query,
+            } => {
+                let mut params = query::parse_params(&request.url);
+                params.retain(|param| param.key != *key);
+                params.push(query::QueryParam::init(key, value));
+                request.url = query::set_params(&request.url, &params);
+            }
+            _ => {}
+        }
+    }
+
+    /// Authorization answering the challenge of a 401 `response` to `request`, for the
+    /// schemes that need one. Without a challenge to answer, it's None
+    pub fn answer_challenge(
+        &self,
+        request: &PreparedRequest,
+        response: &Response,
+    ) -> Result<Option<String>, String> {
+        let (username, password) = match self {
+            Self::Digest { username, password } if response.status == 401 => (username, password),
+            _ => return Ok(None),
+        };
+
+        let challenge = response
+            .headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case("www-authenticate"))
+            .and_then(|(_, value)| DigestChallenge::parse(value));
+
+        match challenge {
+            Some(challenge) => {
+                let cnonce = uuid::Uuid::new_v4().simple().to_string();
+                challenge
+                    .get_authorization(username, password, request, &cnonce)
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+fn set_header(request: &mut PreparedRequest, name: &str, value: String) {
+    request
+        .headers
+        .retain(|key, _| !key.eq_ignore_ascii_case(name));
+    request.headers.insert(name.to_string(), value);
+}
+
+// Parameters of a 'WWW-Authenticate: Digest ...' header, as RFC 7616 says
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DigestChallenge {
+    pub realm: String,
+    pub nonce: String,
+    pub opaque: Option<String>,
+    pub algorithm: String,
+    pub qop: Vec<String>,
+}
+
+impl DigestChallenge {
+    /// Challenge of Digest scheme in `header`, which may have others before it
+    pub fn parse(header: &str) -> Option<Self> {
+        let params = parse_auth_params(find_auth_scheme(header, "digest")?);
+
+        Some(Self {
+            realm: params.get("realm").cloned().unwrap_or_default(),
+            nonce: params.get("nonce")?.clone(),
+            opaque: params.get("opaque").cloned(),
+            algorithm: params
+                .get("algorithm")
+                .cloned()
+                .unwrap_or_else(|| String::from("MD5")),
+            qop: params
+                .get("qop")
+                .map(|qop| qop.split(',').map(|q| q.trim().to_lowercase()).collect())
+                .unwrap_or_default(),
+        })
+    }
+
+    fn hash(&self, value: &str) -> Result<String, String> {
+        let algorithm = self.algorithm.to_uppercase();
+        match algorithm.trim_end_matches("-SESS") {
+            "MD5" => Ok(format!("{:x}", Md5::digest(value.as_bytes()))),
+            "SHA-256" => Ok(format!("{:x}", Sha256::digest(value.as_bytes()))),
+            _ => Err(format!(
+                "Digest algorithm {} is not supported",
+                self.algorithm
+            )),
+        }
+    }
+
+    /// Value of the Authorization header answering this challenge
+    pub fn get_authorization(
+        &self,
+        username: &str,
+        password: &str,
+        request: &PreparedRequest,
+        cnonce: &str,
+    ) -> Result<String, String> {
+        let url = Url::parse(&request.url).map_err(|e| e.to_string())?;
+        let uri = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        let method = request.method.to_string();
+        let nc = "00000001";
+
+        let mut ha1 = self.hash(&format!("{}:{}:{}", username, self.realm, password))?;
+        if self.algorithm.to_uppercase().ends_with("-SESS") {
+            ha1 = self.hash(&format!("{}:{}:{}", ha1, self.nonce, cnonce))?;
+        }
+
+        // 'auth' is preferred, 'auth-int' also signs the body
+        let qop = ["auth", "auth-int"]
+            .into_iter()
+            .find(|qop| self.qop.iter().any(|q| q == qop));
+        let ha2 = match qop {
+            Some("auth-int") => {
+                let body = match &request.raw_body {
+                    Some(raw_body) => String::from_utf8_lossy(raw_body).to_string(),
+                    None => request.body.clone(),
+                };
+                self.hash(&format!("{}:{}:{}", method, uri, self.hash(&body)?))?
+            }
+            _ => self.hash(&format!("{}:{}", method, uri))?,
+        };
+
+        let response = match qop {
+            Some(qop) => self.hash(&format!(
+                "{}:{}:{}:{}:{}:{}",
+                ha1, self.nonce, nc, cnonce, qop, ha2
+            ))?,
+            None => self.hash(&format!("{}:{}:{}", ha1, self.nonce, ha2))?,
+        };
+
+        let mut authorization = format!(
+            "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\", algorithm={}, response=\"{}\"",
+            username, self.realm, self.nonce, uri, self.algorithm, response
+        );
+        if let Some(opaque) = &self.opaque {
+            authorization.push_str(&format!(", opaque=\"{}\"", opaque));
+        }
+        if let Some(qop) = qop {
+            authorization.push_str(&format!(", qop={}, nc={}, cnonce=\"{}\"", qop, nc, cnonce));
+        }
+        Ok(authorization)
+    }
+}
+
+// Text after the `scheme` token of a challenge list, ignoring case. Quoted strings
+// are skipped, so a parameter like 'realm="digest zone"' is not taken as the scheme
+fn find_auth_scheme<'a>(header: &'a str, scheme: &str) -> Option<&'a str> {
+    let bytes = header.as_bytes();
+    let mut quoted = false;
+    let mut escaped = false;
+
+    for (i, &byte) in bytes.iter().enumerate() {
+        if quoted {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => quoted = false,
+                _ => {}
+            }
+            continue;
+        }
+        if byte == b'"' {
+            quoted = true;
+            continue;
+        }
+
+        let starts_token = i == 0 || matches!(bytes[i - 1], b' ' | b'\t' | b',');
+        let end = i + scheme.len();
+        let is_scheme = bytes
+            .get(i..end)
+            .is_some_and(|token| token.eq_ignore_ascii_case(scheme.as_bytes()));
+        if starts_token && is_scheme && matches!(bytes.get(end), None | Some(b' ' | b'\t')) {
+            return Some(&header[end..]);
+        }
+    }
+
+    None
+}
+
+// Pairs like 'realm="a, b", nonce=xyz', keys lowercased and values unquoted
+fn parse_auth_params(text: &str) -> HashMap<String, String> {
+    let mut params = HashMap::new();
+    let mut rest = text.trim_start();
+
+    while let Some((key, after_key)) = rest.split_once('=') {
+        let key = key.trim().trim_start_matches(',').trim().to_lowercase();
+        let after_key = after_key.trim_start();
+
+        let (value, after_value) = match after_key.strip_prefix('"') {
+            Some(quoted) => {
+                let mut value = String::new();
+                let mut chars = quoted.char_indices();
+                let mut end = quoted.len();
+                while let Some((i, c)) = chars.next() {
+                    match c {
+                        '\\' => value.extend(chars.next().map(|(_, c)| c)),
+                        '"' => {
+                            end = i + 1;
+                            break;
+                        }
+                        c => value.push(c),
+                    }
+                }
+                (value, &quoted[end..])
+            }
+            None => {
+                let end = after_key.find(',').unwrap_or(after_key.len());
+                (after_key[..end].trim().to_string(), &after_key[end..])
+            }
+        };
+
+        // A key with spaces is the start of another challenge
+        if key.contains(' ') {
+            break;
+        }
+        params.insert(key, value);
+        rest = after_value
+            .trim_start()
+            .trim_start_matches(',')
+            .trim_start();
+    }
+
+    params
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::web::request::{Request, METHODS};
+
+    #[test]
+    fn should_inherit_default_auth() {
+        let default = Auth::Bearer {
+            token: String::from("abc"),
+        };
+
+        assert_eq!(Auth::Inherit.with_default(&default), default);
+        assert_eq!(Auth::None.with_default(&default), Auth::None);
+    }
+
+    #[test]
+    fn should_set_basic_and_bearer_headers() {
+        let mut request = PreparedRequest {
+            url: String::from("http://localhost"),
+            ..PreparedRequest::from(Request::default())
+        };
+        request
+            .headers
+            .insert(String::from("authorization"), String::from("old"));
+
+        Auth::Basic {
+            username: String::from("Aladdin"),
+            password: String::from("open sesame"),
+        }
+        .apply(&mut request);
+        assert_eq!(request.headers.get("authorization"), None);
+        assert_eq!(
+            request.headers["Authorization"],
+            "Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ=="
+        );
+
+        Auth::Bearer {
+            token: String::from("abc"),
+        }
+        .apply(&mut request);
+        assert_eq!(request.headers["Authorization"], "Bearer abc");
+    }
+
+    #[test]
+    fn should_send_api_key_in_query() {
+        let mut request = PreparedRequest {
+            url: String::from("http://localhost/items?page=2&api_key=old"),
+            ..PreparedRequest::from(Request::default())
+        };
+
+        Auth::ApiKey {
+            key: String::from("api_key"),
+            value: String::from("a b"),
+            location: ApiKeyLocation::Query,
+        }
+        .apply(&mut request);
+
+        assert_eq!(request.url, "http://localhost/items?page=2&api_key=a%20b");
+    }
+
+    #[test]
+    fn should_parse_digest_challenge() {
+        let challenge = DigestChallenge::parse(
+            r#"Basic realm="x", Digest realm="http-auth@example.org", qop="auth, auth-int", algorithm=SHA-256, nonce="7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v", opaque="FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS""#,
+        )
+        .unwrap();
+
+        assert_eq!(challenge.realm, "http-auth@example.org");
+        assert_eq!(challenge.algorithm, "SHA-256");
+        assert_eq!(challenge.qop, vec!["auth", "auth-int"]);
+        assert_eq!(
+            challenge.opaque.as_deref(),
+            Some("FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS")
+        );
+        assert_eq!(DigestChallenge::parse(r#"Basic realm="x""#), None);
+    }
+
+    #[test]
+    fn should_find_digest_scheme_out_of_quoted_text() {
+        let challenge = DigestChallenge::parse(
+            r#"Basic realm="Zürich digest nonce=fake", DIGEST realm="Zürich", nonce="abc""#,
+        )
+        .unwrap();
+
+        assert_eq!(challenge.realm, "Zürich");
+        assert_eq!(challenge.nonce, "abc");
+        assert_eq!(
+            DigestChallenge::parse(r#"Bearer error="use digest nonce=x""#),
+            None
+        );
+    }
+
+    // Example of RFC 7616, section 3.9.1
+    #[test]
+    fn should_answer_digest_challenge() {
+        let challenge = DigestChallenge {
+            realm: String::from("http-auth@example.org"),
+            nonce: String::from("7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v"),
+            opaque: Some(String::from("FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS")),
+            algorithm: String::from("MD5"),
+            qop: vec![String::from("auth")],
+        };
+        let request = PreparedRequest {
+            url: String::from("http://www.example.org/dir/index.html"),
+            ..PreparedRequest::from(Request::default())
+        };
+
+        let authorization = challenge
+            .get_authorization(
+                "Mufasa",
+                "Circle of Life",
+                &request,
+                "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ",
+            )
+            .unwrap();
+
+        assert!(authorization.starts_with("Digest username=\"Mufasa\""));
+        assert!(authorization.contains("uri=\"/dir/index.html\""));
+        assert!(authorization.contains("response=\"8ca523f5e9506fed4657c9700eebdbec\""));
+        assert!(authorization.contains("qop=auth, nc=00000001"));
+
+        let challenge = DigestChallenge {
+            algorithm: String::from("SHA-256"),
+            ..challenge
+        };
+        let authorization = challenge
+            .get_authorization(
+                "Mufasa",
+                "Circle of Life",
+                &request,
+                "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ",
+            )
+            .unwrap();
+        assert!(authorization.contains(
+            "response=\"753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1\""
+        ));
+    }
+
+    #[test]
+    fn should_answer_only_digest_401_responses() {
+        let auth = Auth::Digest {
+            username: String::from("user"),
+            password: String::from("pass"),
+        };
+        let request = PreparedRequest {
+            method: METHODS::POST,
+            url: String::from("http://localhost/private"),
+            ..PreparedRequest::from(Request::default())
+        };
+        let mut response = Response {
+            status: 401,
+            ..Response::default()
+        };
+        response.headers.insert(
+            String::from("www-authenticate"),
+            String::from(r#"Digest realm="r", nonce="n""#),
+        );
+
+        let authorization = auth.answer_challenge(&request, &response).unwrap().unwrap();
+        assert!(authorization.contains("uri=\"/private\""));
+        assert!(!authorization.contains("qop="));
+
+        let bearer = Auth::Bearer {
+            token: String::from("abc"),
+        };
+        assert_eq!(bearer.answer_challenge(&request, &response), Ok(None));
+
+        response.status = 200;
+        assert_eq!(auth.answer_challenge(&request, &response), Ok(None));
+    }
+}
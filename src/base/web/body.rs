@@ -0,0 +1,211 @@
+// Bodies shown by hex dump are cut at this size, the whole of them can be saved to a file
+static HEX_DUMP_LIMIT: usize = 64 * 1024;
+static HEX_DUMP_WIDTH: usize = 16;
+
+// Known signatures of binary formats, used when the server doesn't send a Content-Type
+static MAGIC_NUMBERS: [(&[u8], &str); 8] = [
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF8", "image/gif"),
+    (b"RIFF", "image/webp"),
+    (b"%PDF-", "application/pdf"),
+    (b"\x1f\x8b", "application/gzip"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"\x00asm", "application/wasm"),
+];
+
+/// Content type of a body, from its Content-Type header (without parameters) or,
+/// when missing, from its first bytes. Empty bodies have none
+pub fn detect_content_type(header: Option<&str>, bytes: &[u8]) -> Option<String> {
+    let from_header = header
+        .and_then(|value| value.split(';').next())
+        .map(|mime| mime.trim().to_lowercase())
+        .filter(|mime| !mime.is_empty());
+
+    if from_header.is_some() {
+        return from_header;
+    }
+
+    if bytes.is_empty() {
+        return None;
+    }
+
+    let from_magic = MAGIC_NUMBERS
+        .iter()
+        .find(|(magic, _)| bytes.starts_with(magic))
+        .map(|(_, mime)| mime.to_string());
+
+    Some(from_magic.unwrap_or_else(|| {
+        if is_readable(bytes) {
+            String::from("text/plain")
+        } else {
+            String::from("application/octet-stream")
+        }
+    }))
+}
+
+fn is_text_content_type(mime: &str) -> bool {
+    let (kind, subtype) = mime.split_once('/').unwrap_or((mime, ""));
+
+    kind == "text"
+        || ["json", "xml", "javascript", "x-www-form-urlencoded", "graphql", "x-ndjson", "yaml"]
+            .contains(&subtype)
+        || subtype.ends_with("+json")
+        || subtype.ends_with("+xml")
+}
+
+fn is_readable(bytes: &[u8]) -> bool {
+    std::str::from_utf8(bytes).is_ok() && !bytes.contains(&0)
+}
+
+/// Bodies of text content types (or readable ones) are kept as text, the others
+/// are binary and shown by their hex dump
+pub fn is_binary(content_type: Option<&str>, bytes: &[u8]) -> bool {
+    match content_type {
+        Some(mime) if is_text_content_type(mime) => false,
+        _ => !is_readable(bytes),
+    }
+}
+
+pub fn body_as_text(content_type: Option<&str>, bytes: &[u8]) -> String {
+    if is_binary(content_type, bytes) {
+        hex_dump(bytes)
+    } else {
+        String::from_utf8_lossy(bytes).to_string()
+    }
+}
+
+/// Lines with offset, bytes in hex and their ASCII, as `hexdump -C`
+pub fn hex_dump(bytes: &[u8]) -> String {
+    let shown = &bytes[..bytes.len().min(HEX_DUMP_LIMIT)];
+
+    let mut lines: Vec<String> = shown
+        .chunks(HEX_DUMP_WIDTH)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|b| match b {
+                    0x20..=0x7e => *b as char,
+                    _ => '.',
+                })
+                .collect();
+
+            format!(
+                "{:08x}  {:<width$}  |{}|",
+                i * HEX_DUMP_WIDTH,
+                hex.join(" "),
+                ascii,
+                width = HEX_DUMP_WIDTH * 3 - 1
+            )
+        })
+        .collect();
+
+    if bytes.len() > shown.len() {
+        lines.push(format!(
+            "... {} bytes more, save the response to see all of them",
+            bytes.len() - shown.len()
+        ));
+    }
+
+    lines.join("\n")
+}
+
+/// Rows aligned in columns, the first one is the header. Request bodies built from
+/// parts are shown this way
+pub fn rows_as_table(rows: &[Vec<String>]) -> String {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|i| {
+            rows.iter()
+                .filter_map(|row| row.get(i))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    rows.iter()
+        .map(|row| {
+            let line: Vec<String> = row
+                .iter()
+                .enumerate()
+                .map(|(i, cell)| format!("{:width$}", cell, width = widths[i]))
+                .collect();
+            line.join("  ").trim_end().to_string()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_detect_content_type() {
+        assert_eq!(
+            detect_content_type(Some("application/JSON; charset=utf-8"), b"{}"),
+            Some("application/json".to_string())
+        );
+        assert_eq!(
+            detect_content_type(None, b"\x89PNG\r\n\x1a\n\x00\x00"),
+            Some("image/png".to_string())
+        );
+        assert_eq!(
+            detect_content_type(None, b"hello"),
+            Some("text/plain".to_string())
+        );
+        assert_eq!(
+            detect_content_type(None, &[0xde, 0xad, 0x00]),
+            Some("application/octet-stream".to_string())
+        );
+        assert_eq!(detect_content_type(None, b""), None);
+    }
+
+    #[test]
+    fn should_keep_text_bodies_as_text() {
+        assert!(!is_binary(Some("application/problem+json"), b"{}"));
+        assert!(!is_binary(Some("text/html"), &[0xff, 0xfe]));
+        assert!(!is_binary(Some("application/octet-stream"), b"plain"));
+        assert!(is_binary(Some("image/png"), b"\x89PNG\r\n\x1a\n"));
+    }
+
+    #[test]
+    fn should_dump_bytes_as_hex_and_ascii() {
+        let dump = hex_dump(b"GIF89a\x01\x00\x01\x00\x80\x00\x00\xff\xff\xffABC");
+
+        assert_eq!(
+            dump,
+            "00000000  47 49 46 38 39 61 01 00 01 00 80 00 00 ff ff ff  |GIF89a..........|\n\
+             00000010  41 42 43                                         |ABC|"
+        );
+    }
+
+    #[test]
+    fn should_cut_hex_dump_of_big_bodies() {
+        let bytes = vec![0_u8; HEX_DUMP_LIMIT + 10];
+
+        let dump = hex_dump(&bytes);
+
+        assert_eq!(dump.lines().count(), HEX_DUMP_LIMIT / HEX_DUMP_WIDTH + 1);
+        assert!(dump.ends_with("... 10 bytes more, save the response to see all of them"));
+    }
+
+    #[test]
+    fn should_align_rows_in_columns() {
+        let rows = vec![
+            vec![String::from("KEY"), String::from("VALUE")],
+            vec![String::from("grant_type"), String::from("password")],
+            vec![String::from("a"), String::new()],
+        ];
+
+        assert_eq!(
+            rows_as_table(&rows),
+            "KEY         VALUE\n\
+             grant_type  password\n\
+             a"
+        );
+    }
+}
@@ -1,45 +1,801 @@
 use crate::base::validators::{Validators, ValidatorsHandler};
+use cookie::time::OffsetDateTime;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
-use super::repository::HttpClientRepository;
-use super::request::METHODS;
-use super::{request::Request, response::Response};
+use super::auth::Auth;
+use super::compression;
+use super::graphql::GraphQLResponse;
+use super::multipart::{self, MultipartPart};
+use super::oauth::{OAuth2Config, OAuth2Grant, OAuth2Token, Pkce, RedirectListener, TokenCache};
+use super::options::RequestOptions;
+use super::repository::{EventListener, HttpClientRepository, HttpError, PreparedRequest};
+use super::request::{BodyMode, Request, RequestKind};
+use super::response::Response;
+use super::signing::{RequestSigner, Signer};
+use super::sse::ServerSentEvent;
+
+// Information of a failed attempt that is going to be done again
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryAttempt {
+    pub attempt: u32,
+    pub max_retries: u32,
+    pub reason: String,
+    pub wait: Duration,
+}
 
 pub struct WebClient<T: HttpClientRepository> {
     http_client: T,
     response: Option<Response>,
+    default_options: RequestOptions,
+    default_auth: Auth,
+    default_signer: Option<Signer>,
+    tokens: Arc<TokenCache>,
 }
 
 impl<T> WebClient<T>
 where
     T: HttpClientRepository,
 {
-    pub fn init(repository: T) -> Self {
+    pub fn init(
+        repository: T,
+        default_options: RequestOptions,
+        default_auth: Auth,
+        tokens: Arc<TokenCache>,
+    ) -> Self {
         Self {
             http_client: repository,
             response: None,
+            default_options,
+            default_auth,
+            default_signer: None,
+            tokens,
         }
     }
 
-    pub async fn submit(&self, request: Request) -> Result<Response, String> {
-        let request_to_do =
-            ValidatorsHandler::from(&request).execute([Validators::url_protocol_request()])?;
+    /// Signer of the requests that have none of their own
+    pub fn with_default_signer(mut self, signer: Option<Signer>) -> Self {
+        self.default_signer = signer;
+        self
+    }
+
+    /// `request` with its OAuth2 auth replaced by a Bearer one, with the token cached or
+    /// got of the server. `on_authorize` receives the page where the user allows it
+    pub async fn authorize<A>(
+        &self,
+        mut request: Request,
+        on_authorize: A,
+    ) -> Result<Request, String>
+    where
+        A: Fn(String) + Send + Sync,
+    {
+        let config = match request.auth.with_default(&self.default_auth) {
+            Auth::OAuth2(config) => config,
+            _ => return Ok(request),
+        };
+
+        let token = self
+            .get_oauth2_token(&config, on_authorize)
+            .await
+            .map_err(|e| format!("OAuth2: {}", e))?;
+        request.set_auth(Auth::Bearer {
+            token: token.access_token,
+        });
+        Ok(request)
+    }
+
+    async fn get_oauth2_token<A>(
+        &self,
+        config: &OAuth2Config,
+        on_authorize: A,
+    ) -> Result<OAuth2Token, String>
+    where
+        A: Fn(String) + Send + Sync,
+    {
+        let key = config.get_cache_key();
+        let cached = self.tokens.get(&key);
+
+        if let Some(token) = cached.as_ref() {
+            if !token.is_expired(TokenCache::now()) {
+                return Ok(token.clone());
+            }
+        }
+
+        // Expired tokens are refreshed when possible, a failed refresh starts again
+        let refresh_token = cached.and_then(|token| token.refresh_token);
+        let refreshed = match &refresh_token {
+            Some(refresh_token) => self
+                .request_token(config.refresh_request(refresh_token))
+                .await
+                .ok(),
+            None => None,
+        };
+
+        let mut token = match (refreshed, config.grant) {
+            (Some(token), _) => token,
+            (None, OAuth2Grant::ClientCredentials) => {
+                self.request_token(config.client_credentials_request())
+                    .await?
+            }
+            (None, OAuth2Grant::AuthorizationCode) => {
+                let listener = RedirectListener::bind(config.redirect_port).await?;
+                let redirect_uri = listener.get_redirect_uri()?;
+                let pkce = Pkce::create();
+                let state = uuid::Uuid::new_v4().simple().to_string();
+
+                on_authorize(config.authorization_url(&redirect_uri, &state, &pkce)?);
+                let code = listener.wait_code(&state).await?;
+
+                self.request_token(config.authorization_code_request(&code, &redirect_uri, &pkce))
+                    .await?
+            }
+        };
+
+        // Servers may keep the same refresh token, without sending it again
+        if token.refresh_token.is_none() {
+            token.refresh_token = refresh_token;
+        }
+
+        self.tokens.set(key, token.clone())?;
+        Ok(token)
+    }
 
-        let Request {
-            url, headers, body, ..
-        } = request_to_do;
+    async fn request_token(&self, mut request: PreparedRequest) -> Result<OAuth2Token, String> {
+        request.options = request.options.with_defaults(&self.default_options);
 
-        let response = match request_to_do.method {
-            METHODS::GET => self.http_client.call_get(url, headers).await,
-            METHODS::POST => self.http_client.call_post(url, headers, body).await,
-            METHODS::PUT => self.http_client.call_put(url, headers, body).await,
-            METHODS::PATCH => self.http_client.call_patch(url, headers, body).await,
-            METHODS::HEAD => self.http_client.call_head(url, headers, body).await,
-            METHODS::DELETE => self.http_client.call_delete(url, headers, body).await,
-        }?;
+        let response = self
+            .http_client
+            .execute(request, &|_: ServerSentEvent| {})
+            .await
+            .map_err(|e| e.to_string())?;
+
+        match response.status {
+            200..=299 => OAuth2Token::from_response(&response.body, TokenCache::now()),
+            status => Err(format!(
+                "Token request failed with status {}: {}",
+                status, response.body
+            )),
+        }
+    }
+
+    pub async fn submit<F, E>(
+        &self,
+        request: Request,
+        on_retry: F,
+        on_event: E,
+    ) -> Result<Response, HttpError>
+    where
+        F: Fn(RetryAttempt) + Send + Sync,
+        E: Fn(ServerSentEvent) + Send + Sync + 'static,
+    {
+        let request_to_do = ValidatorsHandler::from(&request).execute([
+            Validators::url_protocol_request(),
+            Validators::graphql_payload_request(),
+            Validators::form_body_request(),
+        ])
+        .map_err(HttpError::InvalidRequest)?;
+
+        let mut request_to_do = PreparedRequest::from(request_to_do);
+        request_to_do.options = request_to_do.options.with_defaults(&self.default_options);
+
+        if request.kind == RequestKind::Http && request.body_mode == BodyMode::Multipart {
+            Self::set_multipart_body(&mut request_to_do, &request.multipart)
+                .map_err(HttpError::InvalidRequest)?;
+        }
+
+        if request_to_do.options.should_gzip_body() {
+            Self::set_gzip_body(&mut request_to_do);
+        }
+
+        let auth = request.auth.with_default(&self.default_auth);
+        auth.apply(&mut request_to_do);
+
+        // Signatures are the last step, nothing of the request may change after them
+        if let Some(signer) = request.signer.as_ref().or(self.default_signer.as_ref()) {
+            signer
+                .sign(&mut request_to_do, OffsetDateTime::now_utc())
+                .map_err(HttpError::InvalidRequest)?;
+        }
+
+        let delivered = Arc::new(AtomicBool::new(false));
+        let delivered_by_stream = delivered.clone();
+        let on_event = move |event: ServerSentEvent| {
+            delivered_by_stream.store(true, Ordering::SeqCst);
+            on_event(event);
+        };
+
+        let mut response = self
+            .execute_with_retries(request_to_do.clone(), &on_retry, &on_event, &delivered)
+            .await?;
+
+        // Schemes like Digest need the challenge of a first response to be sent
+        let challenge = auth
+            .answer_challenge(&request_to_do, &response)
+            .map_err(|e| HttpError::Auth(format!("Digest: {}", e)))?;
+        if let Some(authorization) = challenge {
+            request_to_do
+                .headers
+                .insert(String::from("Authorization"), authorization);
+            response = self
+                .execute_with_retries(request_to_do, &on_retry, &on_event, &delivered)
+                .await?;
+        }
 
-        let response = ValidatorsHandler::from(&response)
-            .execute_ignoring_errors([Validators::set_pretty_json_response()])?;
+        let mut response = ValidatorsHandler::from(&response)
+            .execute_ignoring_errors([Validators::set_pretty_json_response()])
+            .map_err(HttpError::InvalidRequest)?;
+
+        if request.kind == RequestKind::GraphQL {
+            response.graphql = GraphQLResponse::from_body(&response.body);
+        }
 
         Ok(response)
     }
+
+    // The boundary is in the Content-Type, so it replaces the one of Request
+    fn set_multipart_body(
+        request: &mut PreparedRequest,
+        parts: &[MultipartPart],
+    ) -> Result<(), String> {
+        let boundary = multipart::create_boundary();
+        request.raw_body = Some(multipart::build_payload(parts, &boundary)?);
+
+        request
+            .headers
+            .retain(|key, _| !key.eq_ignore_ascii_case("content-type"));
+        request.headers.insert(
+            String::from("Content-Type"),
+            multipart::get_content_type(&boundary),
+        );
+        Ok(())
+    }
+
+    fn set_gzip_body(request: &mut PreparedRequest) {
+        let body = match &request.raw_body {
+            Some(raw_body) => raw_body.clone(),
+            None => request.body.clone().into_bytes(),
+        };
+        if body.is_empty() {
+            return;
+        }
+
+        request.raw_body = Some(compression::gzip(&body));
+        request
+            .headers
+            .retain(|key, _| !key.eq_ignore_ascii_case("content-encoding"));
+        request
+            .headers
+            .insert(String::from("Content-Encoding"), String::from("gzip"));
+    }
+
+    async fn execute_with_retries<F>(
+        &self,
+        request: PreparedRequest,
+        on_retry: F,
+        on_event: &EventListener,
+        delivered: &AtomicBool,
+    ) -> Result<Response, HttpError>
+    where
+        F: Fn(RetryAttempt) + Send + Sync,
+    {
+        let options = request.options.clone();
+        let max_retries = options.get_retries();
+        let mut attempt = 0;
+
+        loop {
+            let result = self.http_client.execute(request.clone(), on_event).await;
+
+            let reason = match &result {
+                // Events already shown would be shown again by a new attempt
+                Err(_) if delivered.load(Ordering::SeqCst) => None,
+                Err(e) if e.is_retryable() => Some(e.to_string()),
+                Ok(response) if options.should_retry_status(response.status) => {
+                    Some(format!("status {}", response.status))
+                }
+                _ => None,
+            };
+
+            match reason {
+                Some(reason) if attempt < max_retries => {
+                    attempt += 1;
+                    let wait = options.get_backoff(attempt);
+
+                    on_retry(RetryAttempt {
+                        attempt,
+                        max_retries,
+                        reason,
+                        wait,
+                    });
+
+                    tokio::time::sleep(wait).await;
+                }
+                _ => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::web::form::FormField;
+    use async_trait::async_trait;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    // Repository that returns the responses in order, one for each call
+    struct MockRepository {
+        responses: Mutex<Vec<Result<Response, HttpError>>>,
+        calls: Mutex<u32>,
+        requests: Mutex<Vec<PreparedRequest>>,
+        // Given to 'on_event' in every call, before its response
+        events: Vec<ServerSentEvent>,
+    }
+
+    impl MockRepository {
+        fn init(mut responses: Vec<Result<Response, HttpError>>) -> Self {
+            responses.reverse();
+            Self {
+                responses: Mutex::new(responses),
+                calls: Mutex::new(0),
+                requests: Mutex::new(vec![]),
+                events: vec![],
+            }
+        }
+    }
+
+    #[async_trait]
+    impl HttpClientRepository for MockRepository {
+        async fn execute(
+            &self,
+            request: PreparedRequest,
+            on_event: &EventListener,
+        ) -> Result<Response, HttpError> {
+            for event in &self.events {
+                on_event(event.clone());
+            }
+            *self.calls.lock().unwrap() += 1;
+            self.requests.lock().unwrap().push(request);
+            self.responses.lock().unwrap().pop().unwrap()
+        }
+    }
+
+    fn response_with_status(status: i32) -> Result<Response, HttpError> {
+        Ok(Response {
+            status,
+            ..Response::default()
+        })
+    }
+
+    fn request_with_retries(retries: u32) -> Request {
+        let mut request = Request::default();
+        request.set_url(String::from("http://localhost"));
+        request.set_options(RequestOptions {
+            retries: Some(retries),
+            retry_on_status: Some(vec![503]),
+            retry_backoff_ms: Some(0),
+            ..RequestOptions::default()
+        });
+        request
+    }
+
+    #[tokio::test]
+    async fn should_retry_failed_attempts() {
+        let client = WebClient::init(
+            MockRepository::init(vec![
+                Err(HttpError::Transport(String::from("connection refused"))),
+                response_with_status(503),
+                response_with_status(200),
+            ]),
+            RequestOptions::default(),
+            Auth::default(),
+            Arc::new(TokenCache::init(HashMap::new())),
+        );
+
+        let attempts = Mutex::new(vec![]);
+        let response = client
+            .submit(
+                request_with_retries(3),
+                |retry| attempts.lock().unwrap().push(retry),
+                |_| {},
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status, 200);
+        assert_eq!(*client.http_client.calls.lock().unwrap(), 3);
+
+        let attempts = attempts.lock().unwrap();
+        assert_eq!(attempts.len(), 2);
+        assert_eq!(attempts[0].reason, "connection refused");
+        assert_eq!(attempts[1].reason, "status 503");
+        assert_eq!(attempts[1].attempt, 2);
+        assert_eq!(attempts[1].max_retries, 3);
+    }
+
+    #[tokio::test]
+    async fn should_not_retry_stream_after_its_events_were_shown() {
+        let mut repository = MockRepository::init(vec![
+            Err(HttpError::Body(String::from("connection reset"))),
+            response_with_status(200),
+        ]);
+        repository.events = vec![ServerSentEvent {
+            event: String::from("message"),
+            id: None,
+            data: String::from("hi"),
+            retry: None,
+            received_at: std::time::SystemTime::now(),
+        }];
+        let client = WebClient::init(
+            repository,
+            RequestOptions::default(),
+            Auth::default(),
+            Arc::new(TokenCache::init(HashMap::new())),
+        );
+        let received = Arc::new(Mutex::new(0));
+        let counter = received.clone();
+
+        let response = client
+            .submit(request_with_retries(3), |_| {}, move |_| {
+                *counter.lock().unwrap() += 1;
+            })
+            .await;
+
+        assert!(matches!(response, Err(HttpError::Body(_))));
+        assert_eq!(*client.http_client.calls.lock().unwrap(), 1);
+        assert_eq!(*received.lock().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn should_stop_when_retries_are_over() {
+        let client = WebClient::init(
+            MockRepository::init(vec![response_with_status(503), response_with_status(503)]),
+            RequestOptions::default(),
+            Auth::default(),
+            Arc::new(TokenCache::init(HashMap::new())),
+        );
+
+        let response = client
+            .submit(request_with_retries(1), |_| {}, |_| {})
+            .await
+            .unwrap();
+
+        assert_eq!(response.status, 503);
+        assert_eq!(*client.http_client.calls.lock().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn should_answer_digest_challenge_of_default_auth() {
+        let mut challenge = Response {
+            status: 401,
+            ..Response::default()
+        };
+        challenge.headers.insert(
+            String::from("www-authenticate"),
+            String::from(r#"Digest realm="r", nonce="n", qop="auth""#),
+        );
+        let client = WebClient::init(
+            MockRepository::init(vec![Ok(challenge), response_with_status(200)]),
+            RequestOptions::default(),
+            Auth::Digest {
+                username: String::from("user"),
+                password: String::from("pass"),
+            },
+            Arc::new(TokenCache::init(HashMap::new())),
+        );
+
+        let response = client
+            .submit(request_with_retries(0), |_| {}, |_| {})
+            .await
+            .unwrap();
+
+        assert_eq!(response.status, 200);
+        assert_eq!(*client.http_client.calls.lock().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn should_fail_as_digest_when_challenge_is_unsupported() {
+        let mut challenge = Response {
+            status: 401,
+            ..Response::default()
+        };
+        challenge.headers.insert(
+            String::from("www-authenticate"),
+            String::from(r#"Digest realm="r", nonce="n", algorithm=SHA-512"#),
+        );
+        let client = WebClient::init(
+            MockRepository::init(vec![Ok(challenge)]),
+            RequestOptions::default(),
+            Auth::Digest {
+                username: String::from("user"),
+                password: String::from("pass"),
+            },
+            Arc::new(TokenCache::init(HashMap::new())),
+        );
+
+        let error = client
+            .submit(request_with_retries(0), |_| {}, |_| {})
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, HttpError::Auth(_)));
+        assert!(error.to_string().starts_with("Auth failed: Digest: "));
+    }
+
+    #[tokio::test]
+    async fn should_sign_request_after_building_it() {
+        let client = WebClient::init(
+            MockRepository::init(vec![response_with_status(200)]),
+            RequestOptions::default(),
+            Auth::default(),
+            Arc::new(TokenCache::init(HashMap::new())),
+        );
+        let mut request = request_with_retries(0);
+        request.set_body_mode(BodyMode::Form);
+        request.set_form(vec![FormField::init("a", "1")]);
+        request.set_auth(Auth::Bearer {
+            token: String::from("abc"),
+        });
+        request.set_signer(Some(
+            serde_json::from_str(r#"{ "type": "hmac", "secret": "key", "template": "{body}" }"#)
+                .unwrap(),
+        ));
+
+        client.submit(request, |_| {}, |_| {}).await.unwrap();
+
+        let requests = client.http_client.requests.lock().unwrap();
+        let timestamp = &requests[0].headers["X-Timestamp"];
+        assert!(!timestamp.is_empty());
+        assert_eq!(requests[0].headers["Authorization"], "Bearer abc");
+        assert_eq!(
+            requests[0].headers["X-Signature"],
+            "b17f9ae9ca05727a9af1d682e302eb7729bf55bf4bef5ba64cebbe8646401b99"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_sign_with_default_signer() {
+        let signer = serde_json::from_str(r#"{ "type": "hmac", "secret": "key" }"#).unwrap();
+        let client = WebClient::init(
+            MockRepository::init(vec![response_with_status(200)]),
+            RequestOptions::default(),
+            Auth::default(),
+            Arc::new(TokenCache::init(HashMap::new())),
+        )
+        .with_default_signer(Some(signer));
+
+        client
+            .submit(request_with_retries(0), |_| {}, |_| {})
+            .await
+            .unwrap();
+
+        let requests = client.http_client.requests.lock().unwrap();
+        assert!(requests[0].headers.contains_key("X-Signature"));
+    }
+
+    #[tokio::test]
+    async fn should_gzip_body_when_asked() {
+        let client = WebClient::init(
+            MockRepository::init(vec![response_with_status(200)]),
+            RequestOptions {
+                gzip_body: Some(true),
+                ..RequestOptions::default()
+            },
+            Auth::default(),
+            Arc::new(TokenCache::init(HashMap::new())),
+        );
+        let mut request = request_with_retries(0);
+        request.set_body(String::from(r#"{"a": 1}"#));
+
+        client.submit(request, |_| {}, |_| {}).await.unwrap();
+
+        let requests = client.http_client.requests.lock().unwrap();
+        assert_eq!(requests[0].headers["Content-Encoding"], "gzip");
+        let raw_body = requests[0].raw_body.as_ref().unwrap();
+        assert_eq!(
+            compression::decode("gzip", raw_body),
+            Ok(Some(br#"{"a": 1}"#.to_vec()))
+        );
+    }
+
+    fn token_response(body: &str) -> Result<Response, HttpError> {
+        Ok(Response {
+            status: 200,
+            body: body.to_string(),
+            ..Response::default()
+        })
+    }
+
+    fn oauth2_config(grant: OAuth2Grant) -> OAuth2Config {
+        OAuth2Config {
+            grant,
+            token_url: String::from("http://auth.local/token"),
+            auth_url: Some(String::from("http://auth.local/authorize")),
+            client_id: String::from("treq"),
+            ..OAuth2Config::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn should_cache_oauth2_token_until_it_expires() {
+        let client = WebClient::init(
+            MockRepository::init(vec![token_response(
+                r#"{ "access_token": "t1", "expires_in": 3600 }"#,
+            )]),
+            RequestOptions::default(),
+            Auth::OAuth2(oauth2_config(OAuth2Grant::ClientCredentials)),
+            Arc::new(TokenCache::init(HashMap::new())),
+        );
+
+        for _ in 0..2 {
+            let request = client.authorize(Request::default(), |_| {}).await.unwrap();
+            assert_eq!(
+                request.auth,
+                Auth::Bearer {
+                    token: String::from("t1")
+                }
+            );
+        }
+
+        assert_eq!(*client.http_client.calls.lock().unwrap(), 1);
+        let requests = client.http_client.requests.lock().unwrap();
+        assert_eq!(requests[0].url, "http://auth.local/token");
+        assert_eq!(
+            requests[0].body,
+            "grant_type=client_credentials&client_id=treq"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_refresh_expired_oauth2_token() {
+        let config = oauth2_config(OAuth2Grant::ClientCredentials);
+        let expired = OAuth2Token {
+            access_token: String::from("old"),
+            refresh_token: Some(String::from("r1")),
+            expires_at: Some(TokenCache::now() - 10),
+        };
+        let tokens = Arc::new(TokenCache::init(HashMap::from([(
+            config.get_cache_key(),
+            expired,
+        )])));
+        let client = WebClient::init(
+            MockRepository::init(vec![token_response(r#"{ "access_token": "new" }"#)]),
+            RequestOptions::default(),
+            Auth::default(),
+            tokens.clone(),
+        );
+
+        let mut request = Request::default();
+        request.set_auth(Auth::OAuth2(config.clone()));
+        let request = client.authorize(request, |_| {}).await.unwrap();
+
+        assert_eq!(
+            request.auth,
+            Auth::Bearer {
+                token: String::from("new")
+            }
+        );
+        let requests = client.http_client.requests.lock().unwrap();
+        assert_eq!(
+            requests[0].body,
+            "grant_type=refresh_token&refresh_token=r1&client_id=treq"
+        );
+        let token = tokens.get(&config.get_cache_key()).unwrap();
+        assert_eq!(token.refresh_token.as_deref(), Some("r1"));
+    }
+
+    #[tokio::test]
+    async fn should_get_oauth2_token_with_authorization_code() {
+        let client = WebClient::init(
+            MockRepository::init(vec![token_response(r#"{ "access_token": "t1" }"#)]),
+            RequestOptions::default(),
+            Auth::OAuth2(oauth2_config(OAuth2Grant::AuthorizationCode)),
+            Arc::new(TokenCache::init(HashMap::new())),
+        );
+
+        // The browser is redirected to the listener, after the user allows it
+        let on_authorize = |url: String| {
+            let params: HashMap<String, String> = super::super::query::parse_params(&url)
+                .into_iter()
+                .map(|param| (param.key, param.value))
+                .collect();
+            assert_eq!(params["code_challenge_method"], "S256");
+
+            let redirect_uri = params["redirect_uri"].clone();
+            let state = params["state"].clone();
+            std::thread::spawn(move || {
+                use std::io::{Read, Write};
+                let addr = redirect_uri
+                    .trim_start_matches("http://")
+                    .trim_end_matches("/callback");
+                let mut stream = std::net::TcpStream::connect(addr).unwrap();
+                write!(
+                    stream,
+                    "GET /callback?code=c0de&state={} HTTP/1.1\r\n\r\n",
+                    state
+                )
+                .unwrap();
+                stream.read_to_end(&mut vec![]).unwrap();
+            });
+        };
+
+        let request = client
+            .authorize(Request::default(), on_authorize)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            request.auth,
+            Auth::Bearer {
+                token: String::from("t1")
+            }
+        );
+        let requests = client.http_client.requests.lock().unwrap();
+        assert!(requests[0].body.starts_with(
+            "grant_type=authorization_code&code=c0de&redirect_uri=http%3A%2F%2F127.0.0.1"
+        ));
+        assert!(requests[0].body.contains("&code_verifier="));
+    }
+
+    #[test]
+    fn should_send_multipart_body_with_its_boundary() {
+        let mut request = Request::default();
+        request.set_body_mode(BodyMode::Multipart);
+        request.set_multipart(vec![MultipartPart {
+            name: String::from("title"),
+            value: multipart::PartValue::Text(String::from("Hello")),
+            content_type: None,
+        }]);
+        let mut request_to_do = PreparedRequest::from(request.clone());
+
+        WebClient::<MockRepository>::set_multipart_body(&mut request_to_do, &request.multipart)
+            .unwrap();
+
+        assert_eq!(request_to_do.headers.len(), 1);
+        let content_type = &request_to_do.headers["Content-Type"];
+        let boundary = content_type
+            .strip_prefix("multipart/form-data; boundary=")
+            .unwrap();
+        let payload = String::from_utf8(request_to_do.raw_body.unwrap()).unwrap();
+        assert!(payload.starts_with(&format!("--{}\r\n", boundary)));
+        assert!(payload.ends_with(&format!("--{}--\r\n", boundary)));
+    }
+
+    #[tokio::test]
+    async fn should_not_retry_invalid_requests() {
+        let client = WebClient::init(
+            MockRepository::init(vec![Err(HttpError::InvalidHeader(String::from("A B")))]),
+            RequestOptions::default(),
+            Auth::default(),
+            Arc::new(TokenCache::init(HashMap::new())),
+        );
+
+        let response = client.submit(request_with_retries(3), |_| {}, |_| {}).await;
+
+        assert!(response.is_err());
+        assert_eq!(*client.http_client.calls.lock().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn should_fail_with_invalid_request_when_file_is_missing() {
+        let client = WebClient::init(
+            MockRepository::init(vec![]),
+            RequestOptions::default(),
+            Auth::default(),
+            Arc::new(TokenCache::init(HashMap::new())),
+        );
+        let mut request = request_with_retries(0);
+        request.set_body_mode(BodyMode::Multipart);
+        request.set_multipart(vec![MultipartPart {
+            name: String::from("photo"),
+            value: multipart::PartValue::File(String::from("/nonexistent/photo.jpg")),
+            content_type: None,
+        }]);
+
+        let response = client.submit(request, |_| {}, |_| {}).await;
+
+        assert!(matches!(response, Err(HttpError::InvalidRequest(_))));
+        assert_eq!(*client.http_client.calls.lock().unwrap(), 0);
+    }
 }
@@ -0,0 +1,134 @@
+use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+
+// Encodings TReq decodes, asked to servers when the request doesn't say others
+pub static ACCEPT_ENCODING: &str = "gzip, deflate, br";
+
+// How a body came in the response, before being decoded
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResponseCompression {
+    pub encoding: String,
+    pub compressed_size: usize,
+    pub decoded_size: usize,
+}
+
+impl ResponseCompression {
+    pub fn to_summary(&self) -> String {
+        format!(
+            "{} {} -> {}",
+            self.encoding,
+            format_size(self.compressed_size),
+            format_size(self.decoded_size)
+        )
+    }
+}
+
+pub fn format_size(bytes: usize) -> String {
+    match bytes {
+        0..=1023 => format!("{}B", bytes),
+        1024..=1048575 => format!("{:.1}KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1}MB", bytes as f64 / 1048576.0),
+    }
+}
+
+fn read_all<R: Read>(mut reader: R) -> std::io::Result<Vec<u8>> {
+    let mut decoded = vec![];
+    reader.read_to_end(&mut decoded)?;
+    Ok(decoded)
+}
+
+// Servers send "deflate" as zlib (RFC 9110), though some send the raw stream
+fn decode_deflate(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    read_all(ZlibDecoder::new(bytes)).or_else(|_| read_all(DeflateDecoder::new(bytes)))
+}
+
+/// Body of a response with `Content-Encoding: <content_encoding>`, decoded. It's None
+/// when there is nothing to decode, or the encoding is not known
+pub fn decode(content_encoding: &str, bytes: &[u8]) -> Result<Option<Vec<u8>>, String> {
+    // Encodings are listed in the order they were applied
+    let encodings: Vec<String> = content_encoding
+        .split(',')
+        .map(|encoding| encoding.trim().to_lowercase())
+        .filter(|encoding| !encoding.is_empty() && encoding != "identity")
+        .collect();
+
+    if encodings.is_empty() || bytes.is_empty() {
+        return Ok(None);
+    }
+
+    let mut decoded = bytes.to_vec();
+    for encoding in encodings.iter().rev() {
+        decoded = match encoding.as_str() {
+            "gzip" | "x-gzip" => read_all(GzDecoder::new(decoded.as_slice())),
+            "deflate" => decode_deflate(&decoded),
+            "br" => read_all(brotli::Decompressor::new(decoded.as_slice(), 4096)),
+            _ => return Ok(None),
+        }
+        .map_err(|e| format!("Invalid {} body: {}", encoding, e))?;
+    }
+
+    Ok(Some(decoded))
+}
+
+pub fn gzip(bytes: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder
+        .write_all(bytes)
+        .and_then(|_| encoder.finish())
+        .expect("Writing in memory doesn't fail")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::ZlibEncoder;
+
+    static TEXT: &[u8] = b"{\"message\": \"hello hello hello hello hello\"}";
+
+    fn brotli(bytes: &[u8]) -> Vec<u8> {
+        let mut compressed = vec![];
+        let mut encoder = brotli::CompressorWriter::new(&mut compressed, 4096, 5, 22);
+        encoder.write_all(bytes).unwrap();
+        drop(encoder);
+        compressed
+    }
+
+    fn zlib(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn should_decode_each_encoding() {
+        assert_eq!(decode("gzip", &gzip(TEXT)), Ok(Some(TEXT.to_vec())));
+        assert_eq!(decode("deflate", &zlib(TEXT)), Ok(Some(TEXT.to_vec())));
+        assert_eq!(decode("BR", &brotli(TEXT)), Ok(Some(TEXT.to_vec())));
+        assert_eq!(
+            decode("deflate, gzip", &gzip(&zlib(TEXT))),
+            Ok(Some(TEXT.to_vec()))
+        );
+    }
+
+    #[test]
+    fn should_keep_bodies_not_encoded_or_unknown() {
+        assert_eq!(decode("identity", TEXT), Ok(None));
+        assert_eq!(decode("zstd", TEXT), Ok(None));
+        assert_eq!(decode("gzip", b""), Ok(None));
+        assert!(decode("gzip", TEXT).is_err());
+    }
+
+    #[test]
+    fn should_summarize_compression() {
+        let compression = ResponseCompression {
+            encoding: String::from("gzip"),
+            compressed_size: 900,
+            decoded_size: 8704,
+        };
+
+        assert_eq!(compression.to_summary(), "gzip 900B -> 8.5KB");
+        assert_eq!(format_size(3 * 1048576), "3.0MB");
+    }
+}
@@ -0,0 +1,436 @@
+use cookie::time::OffsetDateTime;
+use cookie::Cookie;
+use reqwest::cookie::CookieStore;
+use reqwest::header::HeaderValue;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+use crate::utils::file_facades::{data_file::DataFile, FileFacade};
+
+// Common public suffixes, a server can't set a cookie for all the sites under them.
+// Bare TLDs are already refused because they have no dot
+static PUBLIC_SUFFIXES: &[&str] = &[
+    "co.uk",
+    "org.uk",
+    "ac.uk",
+    "gov.uk",
+    "com.au",
+    "net.au",
+    "org.au",
+    "co.nz",
+    "co.jp",
+    "ne.jp",
+    "or.jp",
+    "co.kr",
+    "com.br",
+    "net.br",
+    "org.br",
+    "com.ar",
+    "com.mx",
+    "com.cn",
+    "net.cn",
+    "org.cn",
+    "com.tw",
+    "com.hk",
+    "co.in",
+    "co.za",
+    "com.tr",
+    "com.pl",
+    "co.il",
+    "github.io",
+    "gitlab.io",
+    "herokuapp.com",
+    "netlify.app",
+    "vercel.app",
+    "pages.dev",
+    "workers.dev",
+    "appspot.com",
+    "azurewebsites.net",
+    "cloudfront.net",
+    "blogspot.com",
+];
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredCookie {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    pub path: String,
+
+    // Unix timestamp, None means it is a session cookie
+    #[serde(default)]
+    pub expires: Option<i64>,
+    #[serde(default)]
+    pub host_only: bool,
+    #[serde(default)]
+    pub secure: bool,
+    #[serde(default)]
+    pub http_only: bool,
+}
+
+impl StoredCookie {
+    /// Create a cookie from a 'Set-Cookie' received from `url`, following RFC 6265.
+    /// Returns None if the cookie is not allowed to be set by this url
+    pub fn from_set_cookie(set_cookie: &str, url: &Url, now: i64) -> Option<Self> {
+        let cookie = Cookie::parse(set_cookie).ok()?;
+        let host = url.host_str()?.to_lowercase();
+
+        let (domain, host_only) = match cookie.domain() {
+            Some(domain) if !domain.is_empty() => {
+                let domain = domain.trim_start_matches('.').to_lowercase();
+                if !Self::domain_match(&host, &domain) {
+                    return None;
+                }
+                // A TLD or public suffix is only allowed when it is the host itself
+                if !domain.contains('.') || PUBLIC_SUFFIXES.contains(&domain.as_str()) {
+                    if domain != host {
+                        return None;
+                    }
+                    (host, true)
+                } else {
+                    (domain, false)
+                }
+            }
+            _ => (host, true),
+        };
+
+        let path = match cookie.path() {
+            Some(path) if path.starts_with('/') => path.to_string(),
+            _ => Self::default_path(url),
+        };
+
+        // Max-Age has priority over Expires
+        let expires = match (cookie.max_age(), cookie.expires_datetime()) {
+            (Some(max_age), _) => Some(now + max_age.whole_seconds()),
+            (None, Some(datetime)) => Some(datetime.unix_timestamp()),
+            (None, None) => None,
+        };
+
+        Some(Self {
+            name: cookie.name().to_string(),
+            value: cookie.value().to_string(),
+            domain,
+            path,
+            expires,
+            host_only,
+            secure: cookie.secure().unwrap_or(false),
+            http_only: cookie.http_only().unwrap_or(false),
+        })
+    }
+
+    fn domain_match(host: &str, domain: &str) -> bool {
+        host == domain || host.ends_with(&format!(".{}", domain))
+    }
+
+    fn path_match(request_path: &str, cookie_path: &str) -> bool {
+        request_path == cookie_path
+            || (request_path.starts_with(cookie_path)
+                && (cookie_path.ends_with('/')
+                    || request_path[cookie_path.len()..].starts_with('/')))
+    }
+
+    fn default_path(url: &Url) -> String {
+        let path = url.path();
+        match path.rfind('/') {
+            Some(0) | None => String::from("/"),
+            Some(i) => path[..i].to_string(),
+        }
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        matches!(self.expires, Some(expires) if expires <= now)
+    }
+
+    pub fn is_same(&self, other: &StoredCookie) -> bool {
+        self.name == other.name && self.domain == other.domain && self.path == other.path
+    }
+
+    pub fn matches(&self, url: &Url, now: i64) -> bool {
+        let host = match url.host_str() {
+            Some(host) => host.to_lowercase(),
+            None => return false,
+        };
+
+        let domain_matches = if self.host_only {
+            host == self.domain
+        } else {
+            Self::domain_match(&host, &self.domain)
+        };
+
+        domain_matches
+            && Self::path_match(url.path(), &self.path)
+            && (!self.secure || url.scheme() == "https")
+            && !self.is_expired(now)
+    }
+
+    pub fn get_expires_as_text(&self) -> String {
+        let expires = self
+            .expires
+            .and_then(|t| OffsetDateTime::from_unix_timestamp(t).ok());
+
+        match expires {
+            Some(date) => format!(
+                "{}-{:02}-{:02} {:02}:{:02}",
+                date.year(),
+                date.month() as u8,
+                date.day(),
+                date.hour(),
+                date.minute()
+            ),
+            None => String::from("session"),
+        }
+    }
+}
+
+// Cookies of the workspace, used by the HTTP client to send and receive them.
+// Every change is saved in 'cookies.json' of the data folder
+pub struct CookieJar {
+    cookies: Mutex<Vec<StoredCookie>>,
+    file: Option<Mutex<DataFile>>,
+}
+
+impl CookieJar {
+    pub fn init(cookies: Vec<StoredCookie>) -> Self {
+        Self {
+            cookies: Mutex::new(cookies),
+            file: None,
+        }
+    }
+
+    pub fn setup_and_init() -> Result<Self, String> {
+        DataFile::setup()?;
+        let file = DataFile::from_name(String::from("cookies.json"));
+
+        let cookies = match file.get_content() {
+            Ok(content) => serde_json::from_str(&content).map_err(|e| e.to_string())?,
+            Err(_) => vec![],
+        };
+
+        Ok(Self {
+            cookies: Mutex::new(cookies),
+            file: Some(Mutex::new(file)),
+        })
+    }
+
+    fn now() -> i64 {
+        OffsetDateTime::now_utc().unix_timestamp()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        if let Some(file) = &self.file {
+            let content = serde_json::to_string_pretty(&self.get_persistent_cookies())
+                .map_err(|e| e.to_string())?;
+            file.lock().unwrap().save_content(content)?;
+        }
+        Ok(())
+    }
+
+    pub fn get_cookies(&self) -> Vec<StoredCookie> {
+        self.cookies.lock().unwrap().clone()
+    }
+
+    // Session cookies live only until treq is closed, so they are not saved
+    fn get_persistent_cookies(&self) -> Vec<StoredCookie> {
+        let mut cookies = self.get_cookies();
+        cookies.retain(|cookie| cookie.expires.is_some());
+        cookies
+    }
+
+    pub fn set_all(&self, cookies: Vec<StoredCookie>) -> Result<(), String> {
+        *self.cookies.lock().unwrap() = cookies;
+        self.save()
+    }
+
+    pub fn clear(&self) -> Result<(), String> {
+        self.set_all(vec![])
+    }
+
+    pub fn insert(&self, cookie: StoredCookie) {
+        let now = Self::now();
+        let mut cookies = self.cookies.lock().unwrap();
+
+        cookies.retain(|c| !c.is_same(&cookie) && !c.is_expired(now));
+
+        // A expired cookie received is the way servers delete it
+        if !cookie.is_expired(now) {
+            cookies.push(cookie);
+        }
+    }
+
+    pub fn get_header_value(&self, url: &Url) -> Option<String> {
+        let now = Self::now();
+        let cookies = self.cookies.lock().unwrap();
+
+        let mut matching: Vec<&StoredCookie> =
+            cookies.iter().filter(|c| c.matches(url, now)).collect();
+
+        if matching.is_empty() {
+            return None;
+        }
+
+        // Cookies with longer paths are listed before (RFC 6265, 5.4)
+        matching.sort_by_key(|c| std::cmp::Reverse(c.path.len()));
+
+        let value = matching
+            .iter()
+            .map(|c| format!("{}={}", c.name, c.value))
+            .collect::<Vec<String>>()
+            .join("; ");
+
+        Some(value)
+    }
+}
+
+impl CookieStore for CookieJar {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        let now = Self::now();
+        let mut has_changed = false;
+
+        for header in cookie_headers {
+            let set_cookie = String::from_utf8_lossy(header.as_bytes());
+            if let Some(cookie) = StoredCookie::from_set_cookie(&set_cookie, url, now) {
+                self.insert(cookie);
+                has_changed = true;
+            }
+        }
+
+        if has_changed {
+            self.save().unwrap_or(());
+        }
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        let value = self.get_header_value(url)?;
+        HeaderValue::from_str(&value).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn should_parse_set_cookie() {
+        let cookie = StoredCookie::from_set_cookie(
+            "session=abc; Path=/api; Max-Age=60; HttpOnly",
+            &url("http://localhost:3000/login"),
+            1000,
+        )
+        .unwrap();
+
+        assert_eq!(cookie.name, "session");
+        assert_eq!(cookie.value, "abc");
+        assert_eq!(cookie.domain, "localhost");
+        assert_eq!(cookie.path, "/api");
+        assert_eq!(cookie.expires, Some(1060));
+        assert!(cookie.host_only);
+        assert!(cookie.http_only);
+        assert!(!cookie.secure);
+    }
+
+    #[test]
+    fn should_reject_cookie_of_other_domain() {
+        let cookie = StoredCookie::from_set_cookie(
+            "session=abc; Domain=other.com",
+            &url("http://api.example.com/login"),
+            0,
+        );
+        assert_eq!(cookie, None);
+
+        let cookie = StoredCookie::from_set_cookie(
+            "session=abc; Domain=.example.com",
+            &url("http://api.example.com/login"),
+            0,
+        )
+        .unwrap();
+        assert_eq!(cookie.domain, "example.com");
+        assert!(!cookie.host_only);
+    }
+
+    #[test]
+    fn should_send_only_matching_cookies() {
+        let jar = CookieJar::init(vec![]);
+        let login = url("https://api.example.com/auth/login");
+
+        jar.set_cookies(
+            &mut [
+                HeaderValue::from_static("session=abc; Path=/"),
+                HeaderValue::from_static("scoped=1; Path=/auth"),
+                HeaderValue::from_static("secure=1; Path=/; Secure"),
+                HeaderValue::from_static("wide=1; Domain=example.com; Path=/"),
+            ]
+            .iter(),
+            &login,
+        );
+
+        assert_eq!(
+            jar.get_header_value(&url("https://api.example.com/auth/me")),
+            Some("scoped=1; session=abc; secure=1; wide=1".to_string())
+        );
+        assert_eq!(
+            jar.get_header_value(&url("http://api.example.com/me")),
+            Some("session=abc; wide=1".to_string())
+        );
+        assert_eq!(
+            jar.get_header_value(&url("http://www.example.com/")),
+            Some("wide=1".to_string())
+        );
+        assert_eq!(jar.get_header_value(&url("http://other.com/")), None);
+    }
+
+    #[test]
+    fn should_replace_and_delete_cookies() {
+        let jar = CookieJar::init(vec![]);
+        let site = url("http://localhost/");
+
+        jar.set_cookies(&mut [HeaderValue::from_static("a=1")].iter(), &site);
+        jar.set_cookies(&mut [HeaderValue::from_static("a=2")].iter(), &site);
+        assert_eq!(jar.get_header_value(&site), Some("a=2".to_string()));
+
+        jar.set_cookies(&mut [HeaderValue::from_static("a=2; Max-Age=0")].iter(), &site);
+        assert_eq!(jar.get_header_value(&site), None);
+        assert!(jar.get_cookies().is_empty());
+    }
+
+    #[test]
+    fn should_reject_domain_of_public_suffix() {
+        let site = url("http://www.example.co.uk/");
+        for set_cookie in ["a=1; Domain=uk", "a=1; Domain=co.uk", "a=1; Domain=.co.uk"] {
+            assert_eq!(StoredCookie::from_set_cookie(set_cookie, &site, 0), None);
+        }
+
+        let cookie = StoredCookie::from_set_cookie("a=1; Domain=example.co.uk", &site, 0).unwrap();
+        assert_eq!(cookie.domain, "example.co.uk");
+
+        let cookie =
+            StoredCookie::from_set_cookie("a=1; Domain=localhost", &url("http://localhost/"), 0)
+                .unwrap();
+        assert_eq!(cookie.domain, "localhost");
+        assert!(cookie.host_only);
+    }
+
+    #[test]
+    fn should_not_save_session_cookies() {
+        let jar = CookieJar::init(vec![]);
+        let site = url("http://localhost/");
+
+        jar.set_cookies(
+            &mut [
+                HeaderValue::from_static("session=1"),
+                HeaderValue::from_static("remember=1; Max-Age=3600"),
+            ]
+            .iter(),
+            &site,
+        );
+
+        assert_eq!(jar.get_cookies().len(), 2);
+        let saved = jar.get_persistent_cookies();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].name, "remember");
+    }
+}
@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+
+use super::body;
+
+pub static FORM_CONTENT_TYPE: &str = "application/x-www-form-urlencoded";
+
+fn default_enabled() -> bool {
+    true
+}
+
+// A pair of an application/x-www-form-urlencoded body. Disabled ones are kept, but not sent
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FormField {
+    pub key: String,
+    #[serde(default)]
+    pub value: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+impl FormField {
+    pub fn init(key: &str, value: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            value: value.to_string(),
+            enabled: true,
+        }
+    }
+
+    /// Fields shown in the editor when there is none yet
+    pub fn examples() -> Vec<Self> {
+        vec![
+            Self::init("key", "value"),
+            Self {
+                enabled: false,
+                ..Self::init("disabled", "not sent")
+            },
+        ]
+    }
+}
+
+/// Enabled fields encoded as the body of a form, like "a=1&b=x+y"
+pub fn encode(fields: &[FormField]) -> String {
+    let mut serializer = form_urlencoded::Serializer::new(String::new());
+    for field in fields.iter().filter(|field| field.enabled) {
+        serializer.append_pair(&field.key, &field.value);
+    }
+    serializer.finish()
+}
+
+/// Fields as a table to be shown in the Body, with a mark of the enabled ones
+pub fn fields_as_table(fields: &[FormField]) -> String {
+    if fields.is_empty() {
+        return String::from("No fields, press [e] to add them");
+    }
+
+    let header = vec![String::new(), String::from("KEY"), String::from("VALUE")];
+    let rows = fields.iter().map(|field| {
+        let mark = if field.enabled { "[x]" } else { "[ ]" };
+        vec![mark.to_string(), field.key.clone(), field.value.clone()]
+    });
+
+    body::rows_as_table(&[header].into_iter().chain(rows).collect::<Vec<_>>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_encode_only_enabled_fields() {
+        let fields = vec![
+            FormField::init("a", "1"),
+            FormField::init("b", "x y&z=é"),
+            FormField {
+                enabled: false,
+                ..FormField::init("c", "3")
+            },
+        ];
+
+        assert_eq!(encode(&fields), "a=1&b=x+y%26z%3D%C3%A9");
+        assert_eq!(encode(&[]), "");
+    }
+}
@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+pub mod parser;
+pub mod schema;
+
+// Parts of a GraphQL request besides its query, which is kept as the body of Request
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GraphQLRequest {
+    pub variables: String,
+    pub operation_name: Option<String>,
+}
+
+impl GraphQLRequest {
+    /// JSON sent by POST, as the GraphQL over HTTP spec says
+    pub fn to_payload(&self, query: &str) -> Result<String, String> {
+        let mut payload = Map::new();
+        payload.insert(String::from("query"), Value::from(query));
+
+        if !self.variables.trim().is_empty() {
+            let variables: Value = serde_json::from_str(&self.variables)
+                .map_err(|e| format!("Variables are not a valid JSON: {}", e))?;
+            if !variables.is_object() {
+                return Err(String::from("Variables should be a JSON object"));
+            }
+            payload.insert(String::from("variables"), variables);
+        }
+
+        if let Some(operation_name) = &self.operation_name {
+            payload.insert(
+                String::from("operationName"),
+                Value::from(operation_name.as_str()),
+            );
+        }
+
+        serde_json::to_string(&payload).map_err(|e| e.to_string())
+    }
+}
+
+// Response of a GraphQL server split in what was asked and what went wrong
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GraphQLResponse {
+    pub data: String,
+    pub errors: Vec<String>,
+}
+
+impl GraphQLResponse {
+    /// None when the body is not a GraphQL response, like errors of a proxy
+    pub fn from_body(body: &str) -> Option<Self> {
+        let json: Value = serde_json::from_str(body).ok()?;
+        let json = json.as_object()?;
+        if !json.contains_key("data") && !json.contains_key("errors") {
+            return None;
+        }
+
+        let data = json.get("data").cloned().unwrap_or(Value::Null);
+        let errors = json
+            .get("errors")
+            .and_then(Value::as_array)
+            .map(|errors| errors.iter().map(Self::error_as_text).collect())
+            .unwrap_or_default();
+
+        Some(Self {
+            data: serde_json::to_string_pretty(&data).unwrap_or_default(),
+            errors,
+        })
+    }
+
+    // Like "Not found (at 2:3) path: user.friends.0"
+    fn error_as_text(error: &Value) -> String {
+        let mut text = match error["message"].as_str() {
+            Some(message) => message.to_string(),
+            None => error.to_string(),
+        };
+
+        let locations: Vec<String> = error["locations"]
+            .as_array()
+            .unwrap_or(&vec![])
+            .iter()
+            .map(|location| format!("{}:{}", location["line"], location["column"]))
+            .collect();
+        if !locations.is_empty() {
+            text.push_str(&format!(" (at {})", locations.join(", ")));
+        }
+
+        let path: Vec<String> = error["path"]
+            .as_array()
+            .unwrap_or(&vec![])
+            .iter()
+            .map(|part| match part {
+                Value::String(name) => name.clone(),
+                other => other.to_string(),
+            })
+            .collect();
+        if !path.is_empty() {
+            text.push_str(&format!(" path: {}", path.join(".")));
+        }
+
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_create_payload_with_variables_and_operation() {
+        let request = GraphQLRequest {
+            variables: String::from("{ \"id\": 1 }"),
+            operation_name: Some(String::from("GetUser")),
+        };
+
+        let payload: Value =
+            serde_json::from_str(&request.to_payload("query GetUser { user { id } }").unwrap())
+                .unwrap();
+
+        assert_eq!(
+            payload,
+            serde_json::json!({
+                "query": "query GetUser { user { id } }",
+                "variables": { "id": 1 },
+                "operationName": "GetUser",
+            })
+        );
+    }
+
+    #[test]
+    fn should_not_send_empty_variables() {
+        let payload = GraphQLRequest::default().to_payload("{ a }").unwrap();
+
+        assert_eq!(payload, r#"{"query":"{ a }"}"#);
+    }
+
+    #[test]
+    fn should_fail_with_invalid_variables() {
+        let not_json = GraphQLRequest {
+            variables: String::from("{ id: 1 }"),
+            ..Default::default()
+        };
+        let not_object = GraphQLRequest {
+            variables: String::from("[1]"),
+            ..Default::default()
+        };
+
+        assert!(not_json.to_payload("{ a }").is_err());
+        assert!(not_object.to_payload("{ a }").is_err());
+    }
+
+    #[test]
+    fn should_split_data_and_errors_of_response() {
+        let response = GraphQLResponse::from_body(
+            r#"{
+                "data": { "user": null },
+                "errors": [{
+                    "message": "Not found",
+                    "locations": [{ "line": 2, "column": 3 }],
+                    "path": ["user", "friends", 0]
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(response.data, "{\n  \"user\": null\n}");
+        assert_eq!(
+            response.errors,
+            vec!["Not found (at 2:3) path: user.friends.0"]
+        );
+        assert_eq!(GraphQLResponse::from_body("<html></html>"), None);
+        assert_eq!(
+            GraphQLResponse::from_body(r#"{ "message": "Bad Gateway" }"#),
+            None
+        );
+    }
+}
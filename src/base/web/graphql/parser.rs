@@ -0,0 +1,429 @@
+// Parser of the parts of a GraphQL document needed to know its operations and the
+// fields they select. Arguments, variables and directives are skipped, the server
+// checks them anyway
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OperationKind {
+    Query,
+    Mutation,
+    Subscription,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Selection {
+    Field {
+        name: String,
+        selections: Vec<Selection>,
+    },
+    FragmentSpread(String),
+    InlineFragment {
+        type_condition: Option<String>,
+        selections: Vec<Selection>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Operation {
+    pub kind: OperationKind,
+    pub name: Option<String>,
+    pub selections: Vec<Selection>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fragment {
+    pub name: String,
+    pub type_condition: String,
+    pub selections: Vec<Selection>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Document {
+    pub operations: Vec<Operation>,
+    pub fragments: Vec<Fragment>,
+}
+
+impl Document {
+    pub fn get_operation_names(&self) -> Vec<String> {
+        self.operations
+            .iter()
+            .filter_map(|operation| operation.name.clone())
+            .collect()
+    }
+
+    pub fn get_fragment(&self, name: &str) -> Option<&Fragment> {
+        self.fragments.iter().find(|fragment| fragment.name == name)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Name(String),
+    Punctuator(char),
+    Spread,
+    // Strings and numbers only appear in arguments, their value is never used
+    Value,
+}
+
+impl Token {
+    fn as_text(&self) -> String {
+        match self {
+            Token::Name(name) => name.clone(),
+            Token::Punctuator(c) => c.to_string(),
+            Token::Spread => String::from("..."),
+            Token::Value => String::from("value"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c.is_whitespace() || c == ',' || c == '\u{feff}' {
+            i += 1;
+        } else if c == '#' {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '.' {
+            if chars[i..].starts_with(&['.', '.', '.']) {
+                tokens.push(Token::Spread);
+                i += 3;
+            } else {
+                return Err(String::from("Unexpected '.', did you mean '...'?"));
+            }
+        } else if "!$&()[]{}:=@|".contains(c) {
+            tokens.push(Token::Punctuator(c));
+            i += 1;
+        } else if c == '_' || c.is_ascii_alphabetic() {
+            let start = i;
+            while i < chars.len() && (chars[i] == '_' || chars[i].is_ascii_alphanumeric()) {
+                i += 1;
+            }
+            tokens.push(Token::Name(chars[start..i].iter().collect()));
+        } else if c == '-' || c.is_ascii_digit() {
+            i += 1;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || ".+-".contains(chars[i]))
+            {
+                i += 1;
+            }
+            tokens.push(Token::Value);
+        } else if chars[i..].starts_with(&['"', '"', '"']) {
+            i += 3;
+            loop {
+                if i >= chars.len() {
+                    return Err(String::from("Unterminated block string"));
+                }
+                if chars[i..].starts_with(&['\\', '"', '"', '"']) {
+                    i += 4;
+                } else if chars[i..].starts_with(&['"', '"', '"']) {
+                    i += 3;
+                    break;
+                } else {
+                    i += 1;
+                }
+            }
+            tokens.push(Token::Value);
+        } else if c == '"' {
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None | Some('\n') => return Err(String::from("Unterminated string")),
+                    Some('\\') => i += 2,
+                    Some('"') => {
+                        i += 1;
+                        break;
+                    }
+                    Some(_) => i += 1,
+                }
+            }
+            tokens.push(Token::Value);
+        } else {
+            return Err(format!("Unexpected character '{}'", c));
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Result<Token, String> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .cloned()
+            .ok_or_else(|| String::from("Unexpected end of query"))?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn is_next(&self, c: char) -> bool {
+        self.peek() == Some(&Token::Punctuator(c))
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        match self.next()? {
+            Token::Punctuator(found) if found == c => Ok(()),
+            token => Err(format!("Expected '{}', found '{}'", c, token.as_text())),
+        }
+    }
+
+    fn expect_name(&mut self) -> Result<String, String> {
+        match self.next()? {
+            Token::Name(name) => Ok(name),
+            token => Err(format!("Expected a name, found '{}'", token.as_text())),
+        }
+    }
+
+    // Skips everything until the closing of the bracket already opened
+    fn skip_until_close(&mut self, open: char, close: char) -> Result<(), String> {
+        self.expect(open)?;
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next()? {
+                Token::Punctuator(c) if c == open => depth += 1,
+                Token::Punctuator(c) if c == close => depth -= 1,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn skip_directives(&mut self) -> Result<(), String> {
+        while self.is_next('@') {
+            self.next()?;
+            self.expect_name()?;
+            if self.is_next('(') {
+                self.skip_until_close('(', ')')?;
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_document(&mut self) -> Result<Document, String> {
+        let mut document = Document::default();
+
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Punctuator('{') => document.operations.push(Operation {
+                    kind: OperationKind::Query,
+                    name: None,
+                    selections: self.parse_selection_set()?,
+                }),
+                Token::Name(name) if name == "fragment" => {
+                    self.next()?;
+                    document.fragments.push(self.parse_fragment()?);
+                }
+                Token::Name(_) => {
+                    let operation = self.parse_operation()?;
+                    document.operations.push(operation);
+                }
+                token => return Err(format!("Unexpected '{}'", token.as_text())),
+            }
+        }
+
+        if document.operations.is_empty() {
+            return Err(String::from("Query has no operation"));
+        }
+
+        Ok(document)
+    }
+
+    fn parse_operation(&mut self) -> Result<Operation, String> {
+        let kind = match self.expect_name()?.as_str() {
+            "query" => OperationKind::Query,
+            "mutation" => OperationKind::Mutation,
+            "subscription" => OperationKind::Subscription,
+            other => return Err(format!("Unknown operation '{}'", other)),
+        };
+
+        let name = match self.peek() {
+            Some(Token::Name(_)) => Some(self.expect_name()?),
+            _ => None,
+        };
+
+        if self.is_next('(') {
+            self.skip_until_close('(', ')')?;
+        }
+        self.skip_directives()?;
+
+        Ok(Operation {
+            kind,
+            name,
+            selections: self.parse_selection_set()?,
+        })
+    }
+
+    fn parse_fragment(&mut self) -> Result<Fragment, String> {
+        let name = self.expect_name()?;
+        if self.expect_name()? != "on" {
+            return Err(format!("Expected 'on' in fragment '{}'", name));
+        }
+        let type_condition = self.expect_name()?;
+        self.skip_directives()?;
+
+        Ok(Fragment {
+            name,
+            type_condition,
+            selections: self.parse_selection_set()?,
+        })
+    }
+
+    fn parse_selection_set(&mut self) -> Result<Vec<Selection>, String> {
+        self.expect('{')?;
+        let mut selections = vec![];
+
+        while !self.is_next('}') {
+            selections.push(self.parse_selection()?);
+        }
+        self.expect('}')?;
+
+        if selections.is_empty() {
+            return Err(String::from("Selection set is empty"));
+        }
+
+        Ok(selections)
+    }
+
+    fn parse_selection(&mut self) -> Result<Selection, String> {
+        if self.peek() == Some(&Token::Spread) {
+            self.next()?;
+            return match self.peek() {
+                Some(Token::Name(name)) if name != "on" => {
+                    let name = self.expect_name()?;
+                    self.skip_directives()?;
+                    Ok(Selection::FragmentSpread(name))
+                }
+                Some(Token::Name(_)) => {
+                    self.next()?;
+                    let type_condition = Some(self.expect_name()?);
+                    self.skip_directives()?;
+                    Ok(Selection::InlineFragment {
+                        type_condition,
+                        selections: self.parse_selection_set()?,
+                    })
+                }
+                _ => {
+                    self.skip_directives()?;
+                    Ok(Selection::InlineFragment {
+                        type_condition: None,
+                        selections: self.parse_selection_set()?,
+                    })
+                }
+            };
+        }
+
+        // With an alias, the name of the field comes after it
+        let mut name = self.expect_name()?;
+        if self.is_next(':') {
+            self.next()?;
+            name = self.expect_name()?;
+        }
+
+        if self.is_next('(') {
+            self.skip_until_close('(', ')')?;
+        }
+        self.skip_directives()?;
+
+        let selections = if self.is_next('{') {
+            self.parse_selection_set()?
+        } else {
+            vec![]
+        };
+
+        Ok(Selection::Field { name, selections })
+    }
+}
+
+pub fn parse_document(source: &str) -> Result<Document, String> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        position: 0,
+    };
+    parser.parse_document()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str, selections: Vec<Selection>) -> Selection {
+        Selection::Field {
+            name: name.to_string(),
+            selections,
+        }
+    }
+
+    #[test]
+    fn should_parse_operations_and_fragments() {
+        let document = parse_document(
+            r#"
+            # Comments are ignored
+            query GetUser($id: ID!) @cached {
+                me: user(id: $id, filter: { name: "a, b" }) {
+                    id
+                    ...UserFields
+                    ... on Admin { level }
+                }
+            }
+
+            mutation Logout { logout }
+
+            fragment UserFields on User { name(format: """long "text" here""") }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(document.get_operation_names(), vec!["GetUser", "Logout"]);
+        assert_eq!(document.operations[1].kind, OperationKind::Mutation);
+        assert_eq!(
+            document.operations[0].selections,
+            vec![field(
+                "user",
+                vec![
+                    field("id", vec![]),
+                    Selection::FragmentSpread("UserFields".to_string()),
+                    Selection::InlineFragment {
+                        type_condition: Some("Admin".to_string()),
+                        selections: vec![field("level", vec![])],
+                    },
+                ]
+            )]
+        );
+        assert_eq!(
+            document.get_fragment("UserFields").unwrap().type_condition,
+            "User"
+        );
+    }
+
+    #[test]
+    fn should_parse_shorthand_query() {
+        let document = parse_document("{ users { id } }").unwrap();
+
+        assert_eq!(document.operations.len(), 1);
+        assert_eq!(document.operations[0].name, None);
+        assert_eq!(document.operations[0].kind, OperationKind::Query);
+    }
+
+    #[test]
+    fn should_fail_with_invalid_syntax() {
+        assert!(parse_document("").is_err());
+        assert!(parse_document("query { user { id }").is_err());
+        assert!(parse_document("query { }").is_err());
+        assert!(parse_document("select { id }").is_err());
+        assert!(parse_document("{ name(x: \"open) }").is_err());
+    }
+}
@@ -0,0 +1,299 @@
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+use super::parser::{Document, OperationKind, Selection};
+
+// Fields every type has, and the ones of introspection in the root of queries
+static TYPENAME_FIELD: &str = "__typename";
+static INTROSPECTION_FIELDS: [&str; 2] = ["__schema", "__type"];
+
+pub static INTROSPECTION_OPERATION: &str = "IntrospectionQuery";
+
+// Only what is needed to know the fields of each type. The type of a field is
+// nested in 'ofType' for each List and NonNull wrapping it
+pub static INTROSPECTION_QUERY: &str = "query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    subscriptionType { name }
+    types {
+      name
+      fields(includeDeprecated: true) {
+        name
+        type { name ofType { name ofType { name ofType { name ofType { name ofType { name ofType { name } } } } } } }
+      }
+    }
+  }
+}";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GraphQLSchema {
+    query_type: Option<String>,
+    mutation_type: Option<String>,
+    subscription_type: Option<String>,
+
+    // Fields of object and interface types, with the name of the type of each one
+    types: HashMap<String, HashMap<String, String>>,
+}
+
+impl GraphQLSchema {
+    /// Schema from the response body of the `INTROSPECTION_QUERY`
+    pub fn from_introspection(body: &str) -> Result<Self, String> {
+        let json: Value = serde_json::from_str(body).map_err(|e| e.to_string())?;
+        let schema = json.pointer("/data/__schema").ok_or_else(|| {
+            String::from("Response has no '__schema', introspection may be disabled")
+        })?;
+
+        let root_type = |key: &str| {
+            schema
+                .pointer(&format!("/{}/name", key))
+                .and_then(Value::as_str)
+                .map(String::from)
+        };
+
+        let mut types = HashMap::new();
+        for schema_type in schema["types"].as_array().unwrap_or(&vec![]) {
+            let (Some(name), Some(fields)) = (
+                schema_type["name"].as_str(),
+                schema_type["fields"].as_array(),
+            ) else {
+                continue;
+            };
+
+            let fields = fields
+                .iter()
+                .filter_map(|field| {
+                    let field_name = field["name"].as_str()?;
+                    Some((
+                        field_name.to_string(),
+                        Self::get_named_type(&field["type"])?,
+                    ))
+                })
+                .collect();
+
+            types.insert(name.to_string(), fields);
+        }
+
+        Ok(Self {
+            query_type: root_type("queryType"),
+            mutation_type: root_type("mutationType"),
+            subscription_type: root_type("subscriptionType"),
+            types,
+        })
+    }
+
+    fn get_named_type(field_type: &Value) -> Option<String> {
+        match field_type["name"].as_str() {
+            Some(name) => Some(name.to_string()),
+            None => Self::get_named_type(field_type.get("ofType")?),
+        }
+    }
+
+    pub fn get_types_count(&self) -> usize {
+        self.types.len()
+    }
+
+    /// Checks that every field selected by the operation exists in its type. Without
+    /// `operation_name` the document must have only one operation
+    pub fn validate(
+        &self,
+        document: &Document,
+        operation_name: Option<&str>,
+    ) -> Result<(), String> {
+        let operation = match operation_name {
+            Some(name) => document
+                .operations
+                .iter()
+                .find(|operation| operation.name.as_deref() == Some(name))
+                .ok_or_else(|| format!("Operation '{}' is not in the query", name))?,
+            None if document.operations.len() > 1 => {
+                return Err(String::from(
+                    "Query has many operations, choose which one to send",
+                ))
+            }
+            None => &document.operations[0],
+        };
+
+        let root_type = match operation.kind {
+            OperationKind::Query => &self.query_type,
+            OperationKind::Mutation => &self.mutation_type,
+            OperationKind::Subscription => &self.subscription_type,
+        };
+        let root_type = root_type
+            .as_deref()
+            .ok_or_else(|| format!("Schema has no {:?} type", operation.kind))?;
+
+        let mut errors = vec![];
+        let mut visited_fragments = HashSet::new();
+        for selection in &operation.selections {
+            if let Selection::Field { name, .. } = selection {
+                if operation.kind == OperationKind::Query
+                    && INTROSPECTION_FIELDS.contains(&name.as_str())
+                {
+                    continue;
+                }
+            }
+            self.validate_selection(
+                root_type,
+                selection,
+                document,
+                &mut visited_fragments,
+                &mut errors,
+            );
+        }
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors.join("; ")),
+        }
+    }
+
+    fn validate_selection(
+        &self,
+        type_name: &str,
+        selection: &Selection,
+        document: &Document,
+        visited_fragments: &mut HashSet<String>,
+        errors: &mut Vec<String>,
+    ) {
+        match selection {
+            Selection::Field { name, selections } => {
+                if name == TYPENAME_FIELD {
+                    return;
+                }
+
+                // Scalars, enums and unions have no fields to check
+                let Some(fields) = self.types.get(type_name) else {
+                    return;
+                };
+
+                match fields.get(name) {
+                    Some(field_type) => {
+                        for selection in selections {
+                            self.validate_selection(
+                                field_type,
+                                selection,
+                                document,
+                                visited_fragments,
+                                errors,
+                            );
+                        }
+                    }
+                    None => errors.push(format!(
+                        "Cannot query field '{}' on type '{}'",
+                        name, type_name
+                    )),
+                }
+            }
+            Selection::InlineFragment {
+                type_condition,
+                selections,
+            } => {
+                let type_name = type_condition.as_deref().unwrap_or(type_name);
+                for selection in selections {
+                    self.validate_selection(
+                        type_name,
+                        selection,
+                        document,
+                        visited_fragments,
+                        errors,
+                    );
+                }
+            }
+            Selection::FragmentSpread(name) => {
+                // Each fragment is checked once, it also stops fragments spreading themselves
+                if !visited_fragments.insert(name.clone()) {
+                    return;
+                }
+
+                match document.get_fragment(name) {
+                    Some(fragment) => {
+                        for selection in &fragment.selections {
+                            self.validate_selection(
+                                &fragment.type_condition,
+                                selection,
+                                document,
+                                visited_fragments,
+                                errors,
+                            );
+                        }
+                    }
+                    None => errors.push(format!("Unknown fragment '{}'", name)),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parser::parse_document;
+    use super::*;
+
+    fn schema() -> GraphQLSchema {
+        GraphQLSchema::from_introspection(
+            r#"{ "data": { "__schema": {
+                "queryType": { "name": "Query" },
+                "mutationType": null,
+                "subscriptionType": null,
+                "types": [
+                    { "name": "Query", "fields": [
+                        { "name": "user", "type": { "name": "User", "ofType": null } },
+                        { "name": "users", "type": { "name": null, "ofType": { "name": null, "ofType": { "name": "User" } } } }
+                    ] },
+                    { "name": "User", "fields": [
+                        { "name": "id", "type": { "name": null, "ofType": { "name": "ID" } } },
+                        { "name": "name", "type": { "name": "String" } }
+                    ] },
+                    { "name": "String", "fields": null }
+                ]
+            } } }"#,
+        )
+        .unwrap()
+    }
+
+    fn validate(query: &str, operation_name: Option<&str>) -> Result<(), String> {
+        schema().validate(&parse_document(query).unwrap(), operation_name)
+    }
+
+    #[test]
+    fn should_read_types_of_introspection() {
+        let schema = schema();
+
+        assert_eq!(schema.get_types_count(), 2);
+        assert_eq!(schema.types["Query"]["users"], "User");
+        assert!(GraphQLSchema::from_introspection(r#"{ "data": null }"#).is_err());
+    }
+
+    #[test]
+    fn should_accept_fields_of_schema() {
+        assert_eq!(validate("{ users { id __typename } }", None), Ok(()));
+        assert_eq!(validate("{ __schema { types { name } } }", None), Ok(()));
+        assert_eq!(
+            validate(
+                "query A { user { ...F } } query B { users { id } } fragment F on User { name }",
+                Some("A")
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn should_fail_with_unknown_fields() {
+        assert_eq!(
+            validate("{ user { id email } }", None),
+            Err(String::from("Cannot query field 'email' on type 'User'"))
+        );
+        assert_eq!(
+            validate("{ user { ...Missing } }", None),
+            Err(String::from("Unknown fragment 'Missing'"))
+        );
+        assert!(validate("mutation { logout }", None).is_err());
+    }
+
+    #[test]
+    fn should_fail_without_the_operation_to_send() {
+        assert!(validate("query A { user { id } } query B { user { id } }", None).is_err());
+        assert!(validate("query A { user { id } }", Some("B")).is_err());
+    }
+}
@@ -0,0 +1,501 @@
+use prost::Message;
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor, MethodDescriptor};
+use prost_types::{FileDescriptorProto, FileDescriptorSet};
+use reqwest::Url;
+use std::collections::HashSet;
+use std::fs;
+use std::process::Command;
+use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use tonic::Status;
+
+// gRPC calls are written as URLs like grpc://localhost:50051/package.Service/Method,
+// grpcs:// (or https://) ones use TLS
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GrpcTarget {
+    pub endpoint: String,
+    pub is_tls: bool,
+    pub service: Option<String>,
+    pub method: Option<String>,
+}
+
+impl GrpcTarget {
+    pub fn parse(url: &str) -> Result<Self, String> {
+        let url = Url::parse(url).map_err(|e| e.to_string())?;
+        let is_tls = match url.scheme() {
+            "grpc" | "http" => false,
+            "grpcs" | "https" => true,
+            scheme => return Err(format!("'{}' is not a scheme of gRPC", scheme)),
+        };
+
+        let host = url.host_str().ok_or("URL has no host")?;
+        let scheme = if is_tls { "https" } else { "http" };
+        let endpoint = match url.port() {
+            Some(port) => format!("{}://{}:{}", scheme, host, port),
+            None => format!("{}://{}", scheme, host),
+        };
+
+        let mut path = url.path().trim_matches('/').splitn(2, '/');
+        let service = path.next().filter(|s| !s.is_empty()).map(String::from);
+        let method = path.next().filter(|s| !s.is_empty()).map(String::from);
+
+        Ok(Self {
+            endpoint,
+            is_tls,
+            service,
+            method,
+        })
+    }
+
+    /// Method of the URL in the descriptors, as "/package.Service/Method"
+    pub fn find_method(&self, pool: &DescriptorPool) -> Result<MethodDescriptor, String> {
+        let (Some(service_name), Some(method_name)) = (&self.service, &self.method) else {
+            return Err(String::from(
+                "URL should end with /package.Service/Method, fetch the methods to choose one",
+            ));
+        };
+
+        let service = pool
+            .get_service_by_name(service_name)
+            .ok_or_else(|| format!("Service '{}' not found", service_name))?;
+
+        let method = service
+            .methods()
+            .find(|method| method.name() == method_name);
+        method.ok_or_else(|| format!("Method '{}' not found in '{}'", method_name, service_name))
+    }
+
+    /// Method of the URL as "package.Service/Method", like `get_method_names` gives them
+    pub fn get_method_name(&self) -> Option<String> {
+        Some(format!(
+            "{}/{}",
+            self.service.as_ref()?,
+            self.method.as_ref()?
+        ))
+    }
+}
+
+/// `url` calling `method_name` ("package.Service/Method") instead of the method it has
+pub fn set_method_of_url(url: &str, method_name: &str) -> Result<String, String> {
+    let mut url = Url::parse(url).map_err(|e| e.to_string())?;
+    url.set_path(method_name);
+    Ok(url.to_string())
+}
+
+/// All methods of the descriptors, as "package.Service/Method"
+pub fn get_method_names(pool: &DescriptorPool) -> Vec<String> {
+    pool.services()
+        .flat_map(|service| {
+            service
+                .methods()
+                .map(|method| format!("{}/{}", service.full_name(), method.name()))
+                .collect::<Vec<String>>()
+        })
+        .collect()
+}
+
+/// Lines describing each method, like "helloworld.Greeter/SayHello (HelloRequest) returns (stream HelloReply)"
+pub fn describe_methods(pool: &DescriptorPool) -> String {
+    let stream = |is_streaming: bool| if is_streaming { "stream " } else { "" };
+
+    pool.services()
+        .flat_map(|service| {
+            service
+                .methods()
+                .map(|method| {
+                    format!(
+                        "{}/{} ({}{}) returns ({}{})",
+                        service.full_name(),
+                        method.name(),
+                        stream(method.is_client_streaming()),
+                        method.input().full_name(),
+                        stream(method.is_server_streaming()),
+                        method.output().full_name(),
+                    )
+                })
+                .collect::<Vec<String>>()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub fn pool_from_files(files: Vec<FileDescriptorProto>) -> Result<DescriptorPool, String> {
+    let mut pool = DescriptorPool::new();
+    pool.add_file_descriptor_protos(files)
+        .map_err(|e| e.to_string())?;
+    Ok(pool)
+}
+
+/// Files imported by `files` that are not among them
+pub fn get_missing_dependencies(files: &[FileDescriptorProto]) -> Vec<String> {
+    let names: HashSet<&str> = files.iter().map(|file| file.name()).collect();
+
+    let mut missing: Vec<String> = files
+        .iter()
+        .flat_map(|file| file.dependency.iter())
+        .filter(|dependency| !names.contains(dependency.as_str()))
+        .cloned()
+        .collect();
+    missing.sort();
+    missing.dedup();
+    missing
+}
+
+/// Adds the files not added yet. Servers answer each symbol with its file and the ones
+/// it imports, so files shared by services come more than once
+pub fn add_files(files: &mut Vec<FileDescriptorProto>, new_files: Vec<FileDescriptorProto>) {
+    let mut names: HashSet<String> = files.iter().map(|file| file.name().to_string()).collect();
+
+    for file in new_files {
+        if names.insert(file.name().to_string()) {
+            files.push(file);
+        }
+    }
+}
+
+/// Descriptors of local files. The .proto ones are compiled by `protoc`, any other is read
+/// as a descriptor set (made by `protoc --include_imports --descriptor_set_out`)
+pub fn load_descriptor_files(paths: &[String]) -> Result<DescriptorPool, String> {
+    let mut files = vec![];
+
+    for path in paths {
+        let bytes = if path.ends_with(".proto") {
+            compile_proto_file(path)?
+        } else {
+            fs::read(path).map_err(|e| format!("{}: {}", path, e))?
+        };
+
+        let set = FileDescriptorSet::decode(bytes.as_slice())
+            .map_err(|e| format!("{}: not a descriptor set ({})", path, e))?;
+        files.extend(set.file);
+    }
+
+    pool_from_files(files)
+}
+
+fn compile_proto_file(path: &str) -> Result<Vec<u8>, String> {
+    let include = std::path::Path::new(path)
+        .parent()
+        .map(|dir| dir.to_string_lossy().to_string())
+        .filter(|dir| !dir.is_empty())
+        .unwrap_or_else(|| String::from("."));
+
+    let output = Command::new("protoc")
+        .arg(format!("--proto_path={}", include))
+        .arg("--include_imports")
+        .arg("--descriptor_set_out=/dev/stdout")
+        .arg(path)
+        .output()
+        .map_err(|e| format!("protoc is needed to read {}: {}", path, e))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    Ok(output.stdout)
+}
+
+/// Messages of the body, a JSON object or, for client streaming methods, a list of them
+pub fn messages_from_json(
+    body: &str,
+    method: &MethodDescriptor,
+) -> Result<Vec<DynamicMessage>, String> {
+    let body = if body.trim().is_empty() { "{}" } else { body };
+    let json: serde_json::Value =
+        serde_json::from_str(body).map_err(|e| format!("Body is not a valid JSON: {}", e))?;
+
+    let values = match json {
+        serde_json::Value::Array(values) if method.is_client_streaming() => values,
+        serde_json::Value::Array(_) => {
+            return Err(String::from(
+                "Only client streaming methods send a list of messages",
+            ))
+        }
+        value => vec![value],
+    };
+
+    values
+        .into_iter()
+        .map(|value| {
+            DynamicMessage::deserialize(method.input(), value)
+                .map_err(|e| format!("Body is not a {}: {}", method.input().full_name(), e))
+        })
+        .collect()
+}
+
+/// Messages received as pretty JSON. Server streaming methods show the list of them
+pub fn messages_to_json(messages: &[DynamicMessage], method: &MethodDescriptor) -> String {
+    let values: Vec<serde_json::Value> = messages
+        .iter()
+        .filter_map(|message| serde_json::to_value(message).ok())
+        .collect();
+
+    let json = match (method.is_server_streaming(), values.first()) {
+        (true, _) => serde_json::Value::Array(values),
+        (false, Some(value)) => value.clone(),
+        (false, None) => serde_json::Value::Null,
+    };
+
+    serde_json::to_string_pretty(&json).unwrap_or_default()
+}
+
+// Status of a finished call, as the gRPC spec names each code
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GrpcStatus {
+    pub code: i32,
+    pub message: String,
+}
+
+impl GrpcStatus {
+    pub fn from_status(status: &Status) -> Self {
+        Self {
+            code: status.code() as i32,
+            message: status.message().to_string(),
+        }
+    }
+
+    pub fn ok() -> Self {
+        Self {
+            code: 0,
+            message: String::new(),
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.code == 0
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self.code {
+            0 => "OK",
+            1 => "CANCELLED",
+            2 => "UNKNOWN",
+            3 => "INVALID_ARGUMENT",
+            4 => "DEADLINE_EXCEEDED",
+            5 => "NOT_FOUND",
+            6 => "ALREADY_EXISTS",
+            7 => "PERMISSION_DENIED",
+            8 => "RESOURCE_EXHAUSTED",
+            9 => "FAILED_PRECONDITION",
+            10 => "ABORTED",
+            11 => "OUT_OF_RANGE",
+            12 => "UNIMPLEMENTED",
+            13 => "INTERNAL",
+            14 => "UNAVAILABLE",
+            15 => "DATA_LOSS",
+            16 => "UNAUTHENTICATED",
+            _ => "UNKNOWN",
+        }
+    }
+}
+
+// Codec of messages known only by their descriptors
+#[derive(Clone)]
+pub struct DynamicCodec {
+    output: MessageDescriptor,
+}
+
+impl DynamicCodec {
+    pub fn init(output: MessageDescriptor) -> Self {
+        Self { output }
+    }
+}
+
+impl Codec for DynamicCodec {
+    type Encode = DynamicMessage;
+    type Decode = DynamicMessage;
+    type Encoder = DynamicCodec;
+    type Decoder = DynamicCodec;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        self.clone()
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        self.clone()
+    }
+}
+
+impl Encoder for DynamicCodec {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        item.encode(dst)
+            .map_err(|e| Status::internal(e.to_string()))
+    }
+}
+
+impl Decoder for DynamicCodec {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        DynamicMessage::decode(self.output.clone(), src)
+            .map(Some)
+            .map_err(|e| Status::internal(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use prost_types::{
+        field_descriptor_proto::{Label, Type},
+        DescriptorProto, FieldDescriptorProto, MethodDescriptorProto, ServiceDescriptorProto,
+    };
+
+    /// Descriptors of a service "test.Echo" with methods "Say" and "Count" (server streaming)
+    pub fn echo_descriptors() -> FileDescriptorProto {
+        let method = |name: &str, server_streaming: bool| MethodDescriptorProto {
+            name: Some(name.to_string()),
+            input_type: Some(String::from(".test.EchoRequest")),
+            output_type: Some(String::from(".test.EchoRequest")),
+            server_streaming: Some(server_streaming),
+            ..Default::default()
+        };
+
+        FileDescriptorProto {
+            name: Some(String::from("echo.proto")),
+            package: Some(String::from("test")),
+            syntax: Some(String::from("proto3")),
+            message_type: vec![DescriptorProto {
+                name: Some(String::from("EchoRequest")),
+                field: vec![FieldDescriptorProto {
+                    name: Some(String::from("text")),
+                    json_name: Some(String::from("text")),
+                    number: Some(1),
+                    label: Some(Label::Optional as i32),
+                    r#type: Some(Type::String as i32),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            service: vec![ServiceDescriptorProto {
+                name: Some(String::from("Echo")),
+                method: vec![method("Say", false), method("Count", true)],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn echo_method(name: &str) -> MethodDescriptor {
+        let pool = pool_from_files(vec![echo_descriptors()]).unwrap();
+        GrpcTarget::parse(&format!("grpc://localhost/test.Echo/{}", name))
+            .unwrap()
+            .find_method(&pool)
+            .unwrap()
+    }
+
+    #[test]
+    fn should_parse_url_of_call() {
+        let target = GrpcTarget::parse("grpcs://api.example.com:8443/test.Echo/Say").unwrap();
+
+        assert_eq!(target.endpoint, "https://api.example.com:8443");
+        assert!(target.is_tls);
+        assert_eq!(target.service.as_deref(), Some("test.Echo"));
+        assert_eq!(target.method.as_deref(), Some("Say"));
+
+        let target = GrpcTarget::parse("grpc://localhost:50051").unwrap();
+        assert_eq!(target.endpoint, "http://localhost:50051");
+        assert_eq!(target.service, None);
+
+        assert!(GrpcTarget::parse("ws://localhost").is_err());
+    }
+
+    #[test]
+    fn should_change_method_of_url() {
+        let url = set_method_of_url("grpc://localhost:50051/", "test.Echo/Say").unwrap();
+        assert_eq!(url, "grpc://localhost:50051/test.Echo/Say");
+
+        let url = set_method_of_url(&url, "test.Echo/Count").unwrap();
+        assert_eq!(
+            GrpcTarget::parse(&url)
+                .unwrap()
+                .get_method_name()
+                .as_deref(),
+            Some("test.Echo/Count")
+        );
+    }
+
+    #[test]
+    fn should_find_methods_of_descriptors() {
+        let pool = pool_from_files(vec![echo_descriptors()]).unwrap();
+
+        assert_eq!(
+            get_method_names(&pool),
+            vec!["test.Echo/Say", "test.Echo/Count"]
+        );
+        assert_eq!(
+            describe_methods(&pool),
+            "test.Echo/Say (test.EchoRequest) returns (test.EchoRequest)\n\
+             test.Echo/Count (test.EchoRequest) returns (stream test.EchoRequest)"
+        );
+
+        let missing = GrpcTarget::parse("grpc://localhost/test.Echo/Shout").unwrap();
+        assert!(missing.find_method(&pool).is_err());
+    }
+
+    #[test]
+    fn should_convert_messages_from_and_to_json() {
+        let method = echo_method("Say");
+
+        let messages = messages_from_json(r#"{ "text": "hi" }"#, &method).unwrap();
+        assert_eq!(
+            messages_to_json(&messages, &method),
+            "{\n  \"text\": \"hi\"\n}"
+        );
+
+        assert!(messages_from_json(r#"{ "other": 1 }"#, &method).is_err());
+        assert!(messages_from_json(r#"[{ "text": "hi" }]"#, &method).is_err());
+
+        let streaming = echo_method("Count");
+        assert_eq!(
+            messages_to_json(&[messages[0].clone(), messages[0].clone()], &streaming),
+            "[\n  {\n    \"text\": \"hi\"\n  },\n  {\n    \"text\": \"hi\"\n  }\n]"
+        );
+    }
+
+    #[test]
+    fn should_read_descriptor_set_files() {
+        let set = FileDescriptorSet {
+            file: vec![echo_descriptors()],
+        };
+        let file = tempfile::NamedTempFile::new().unwrap();
+        fs::write(file.path(), set.encode_to_vec()).unwrap();
+
+        let pool = load_descriptor_files(&[file.path().to_string_lossy().to_string()]).unwrap();
+
+        assert_eq!(get_method_names(&pool).len(), 2);
+        assert!(load_descriptor_files(&[String::from("/nonexistent/api.pb")]).is_err());
+    }
+
+    #[test]
+    fn should_find_missing_dependencies() {
+        let mut file = echo_descriptors();
+        file.dependency = vec![
+            String::from("google/protobuf/empty.proto"),
+            String::from("echo.proto"),
+        ];
+
+        assert_eq!(
+            get_missing_dependencies(&[file]),
+            vec!["google/protobuf/empty.proto"]
+        );
+    }
+
+    #[test]
+    fn should_add_each_file_once() {
+        let named = |name: &str| FileDescriptorProto {
+            name: Some(name.to_string()),
+            ..Default::default()
+        };
+        let mut files = vec![];
+
+        add_files(&mut files, vec![named("a.proto"), named("timestamp.proto")]);
+        add_files(&mut files, vec![named("b.proto"), named("timestamp.proto")]);
+
+        let names: Vec<&str> = files.iter().map(|file| file.name()).collect();
+        assert_eq!(names, vec!["a.proto", "timestamp.proto", "b.proto"]);
+    }
+}
@@ -1,4 +1,19 @@
+pub mod auth;
+pub mod body;
 pub mod client;
+pub mod compression;
+pub mod cookies;
+pub mod form;
+pub mod graphql;
+pub mod grpc;
+pub mod multipart;
+pub mod oauth;
+pub mod options;
+pub mod proxy;
+pub mod query;
 pub mod repository;
 pub mod request;
 pub mod response;
+pub mod signing;
+pub mod sse;
+pub mod websocket;
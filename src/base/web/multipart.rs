@@ -0,0 +1,192 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use super::body;
+
+static DEFAULT_FILE_CONTENT_TYPE: &str = "application/octet-stream";
+
+// Value of a part, written in the editor as { "text": "..." } or { "file": "/path" }
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PartValue {
+    Text(String),
+    File(String),
+}
+
+// One field of a multipart/form-data body. Files are read when the request is sent
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultipartPart {
+    pub name: String,
+    #[serde(flatten)]
+    pub value: PartValue,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+}
+
+impl MultipartPart {
+    /// Parts shown in the editor when there is none yet
+    pub fn examples() -> Vec<Self> {
+        vec![
+            Self {
+                name: String::from("field"),
+                value: PartValue::Text(String::from("value")),
+                content_type: None,
+            },
+            Self {
+                name: String::from("attachment"),
+                value: PartValue::File(String::from("/path/to/file")),
+                content_type: None,
+            },
+        ]
+    }
+}
+
+pub fn create_boundary() -> String {
+    format!("treq-{}", uuid::Uuid::new_v4().simple())
+}
+
+pub fn get_content_type(boundary: &str) -> String {
+    format!("multipart/form-data; boundary={}", boundary)
+}
+
+// Names and filenames are quoted, so quotes and line breaks are percent-encoded as
+// browsers do
+fn escape_quoted(value: &str) -> String {
+    value
+        .replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// The body of `parts` separated by `boundary`, as RFC 7578 says
+pub fn build_payload(parts: &[MultipartPart], boundary: &str) -> Result<Vec<u8>, String> {
+    let mut payload = vec![];
+
+    for part in parts {
+        let (content, filename) = match &part.value {
+            PartValue::Text(text) => (text.as_bytes().to_vec(), None),
+            PartValue::File(path) => {
+                let content = fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+                let filename = Path::new(path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path.clone());
+                (content, Some(filename))
+            }
+        };
+
+        payload.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+
+        let mut disposition = format!(
+            "Content-Disposition: form-data; name=\"{}\"",
+            escape_quoted(&part.name)
+        );
+        if let Some(filename) = &filename {
+            disposition.push_str(&format!("; filename=\"{}\"", escape_quoted(filename)));
+        }
+        payload.extend_from_slice(format!("{}\r\n", disposition).as_bytes());
+
+        let content_type = match (&part.content_type, &filename) {
+            (Some(content_type), _) => Some(content_type.as_str()),
+            (None, Some(_)) => Some(DEFAULT_FILE_CONTENT_TYPE),
+            (None, None) => None,
+        };
+        if let Some(content_type) = content_type {
+            payload.extend_from_slice(format!("Content-Type: {}\r\n", content_type).as_bytes());
+        }
+
+        payload.extend_from_slice(b"\r\n");
+        payload.extend_from_slice(&content);
+        payload.extend_from_slice(b"\r\n");
+    }
+
+    payload.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+    Ok(payload)
+}
+
+/// Parts as a table to be shown in the Body, files are marked with '@' as in curl
+pub fn parts_as_table(parts: &[MultipartPart]) -> String {
+    if parts.is_empty() {
+        return String::from("No parts, press [e] to add them");
+    }
+
+    let header = vec![
+        String::from("NAME"),
+        String::from("VALUE"),
+        String::from("CONTENT TYPE"),
+    ];
+    let rows = parts.iter().map(|part| {
+        let value = match &part.value {
+            PartValue::Text(text) => text.replace('\n', "\\n"),
+            PartValue::File(path) => format!("@{}", path),
+        };
+        let content_type = part.content_type.clone().unwrap_or_default();
+        vec![part.name.clone(), value, content_type]
+    });
+
+    body::rows_as_table(&[header].into_iter().chain(rows).collect::<Vec<_>>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn should_build_payload_with_text_and_files() {
+        let mut file = tempfile::Builder::new().suffix(".bin").tempfile().unwrap();
+        file.write_all(b"\x00\x01\x02").unwrap();
+        let path = file.path().to_str().unwrap().to_string();
+        let filename = file
+            .path()
+            .file_name()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        let parts = vec![
+            MultipartPart {
+                name: String::from("title"),
+                value: PartValue::Text(String::from("Hello")),
+                content_type: None,
+            },
+            MultipartPart {
+                name: String::from("data"),
+                value: PartValue::File(path),
+                content_type: None,
+            },
+        ];
+
+        let payload = build_payload(&parts, "XYZ").unwrap();
+
+        let mut expected = b"--XYZ\r\n\
+            Content-Disposition: form-data; name=\"title\"\r\n\
+            \r\n\
+            Hello\r\n\
+            --XYZ\r\n"
+            .to_vec();
+        expected.extend_from_slice(
+            format!(
+                "Content-Disposition: form-data; name=\"data\"; filename=\"{}\"\r\n\
+                Content-Type: application/octet-stream\r\n\r\n",
+                filename
+            )
+            .as_bytes(),
+        );
+        expected.extend_from_slice(b"\x00\x01\x02\r\n--XYZ--\r\n");
+        assert_eq!(payload, expected);
+    }
+
+    #[test]
+    fn should_fail_with_missing_files() {
+        let parts = vec![MultipartPart {
+            name: String::from("data"),
+            value: PartValue::File(String::from("/not/a/file")),
+            content_type: None,
+        }];
+
+        assert!(build_payload(&parts, "XYZ").is_err());
+    }
+}
@@ -0,0 +1,512 @@
+use base64::Engine;
+use cookie::time::OffsetDateTime;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use super::form::{self, FormField, FORM_CONTENT_TYPE};
+use super::query;
+use super::repository::PreparedRequest;
+use super::request::{Request, METHODS};
+use crate::utils::file_facades::{data_file::DataFile, FileFacade};
+
+// Tokens are renewed this long before they expire, so they don't during the request
+static EXPIRATION_MARGIN_SECS: i64 = 30;
+static AUTHORIZATION_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OAuth2Grant {
+    #[default]
+    ClientCredentials,
+    // With PKCE, the code is received by a listener in 'redirect_port' of localhost
+    AuthorizationCode,
+}
+
+impl OAuth2Grant {
+    pub fn get_name(&self) -> &str {
+        match self {
+            Self::ClientCredentials => "client_credentials",
+            Self::AuthorizationCode => "authorization_code",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OAuth2Config {
+    #[serde(default)]
+    pub grant: OAuth2Grant,
+    pub token_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_url: Option<String>,
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    // Without it, any free port is used
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redirect_port: Option<u16>,
+}
+
+impl OAuth2Config {
+    /// Tokens are shared by every request with the same server, grant, client and scope.
+    /// The secret is in the key as part of its hash, to not be saved with the tokens
+    pub fn get_cache_key(&self) -> String {
+        let secret_hash = format!("{:x}", Sha256::digest(self.client_secret.as_bytes()));
+        format!(
+            "{} {} {} {} {}",
+            self.token_url,
+            self.grant.get_name(),
+            self.client_id,
+            &secret_hash[..16],
+            self.scope.as_deref().unwrap_or_default()
+        )
+    }
+
+    fn get_client_fields(&self) -> Vec<FormField> {
+        let mut fields = vec![FormField::init("client_id", &self.client_id)];
+        if !self.client_secret.is_empty() {
+            fields.push(FormField::init("client_secret", &self.client_secret));
+        }
+        fields
+    }
+
+    fn get_scope_fields(&self) -> Vec<FormField> {
+        self.scope
+            .iter()
+            .map(|scope| FormField::init("scope", scope))
+            .collect()
+    }
+
+    pub fn client_credentials_request(&self) -> PreparedRequest {
+        let mut fields = vec![FormField::init("grant_type", "client_credentials")];
+        fields.extend(self.get_scope_fields());
+        fields.extend(self.get_client_fields());
+        self.token_request(fields)
+    }
+
+    pub fn refresh_request(&self, refresh_token: &str) -> PreparedRequest {
+        let mut fields = vec![
+            FormField::init("grant_type", "refresh_token"),
+            FormField::init("refresh_token", refresh_token),
+        ];
+        fields.extend(self.get_scope_fields());
+        fields.extend(self.get_client_fields());
+        self.token_request(fields)
+    }
+
+    pub fn authorization_code_request(
+        &self,
+        code: &str,
+        redirect_uri: &str,
+        pkce: &Pkce,
+    ) -> PreparedRequest {
+        let mut fields = vec![
+            FormField::init("grant_type", "authorization_code"),
+            FormField::init("code", code),
+            FormField::init("redirect_uri", redirect_uri),
+            FormField::init("code_verifier", &pkce.verifier),
+        ];
+        fields.extend(self.get_client_fields());
+        self.token_request(fields)
+    }
+
+    /// Page of the server where the user allows the access, it redirects to `redirect_uri`
+    pub fn authorization_url(
+        &self,
+        redirect_uri: &str,
+        state: &str,
+        pkce: &Pkce,
+    ) -> Result<String, String> {
+        let auth_url = self
+            .auth_url
+            .as_ref()
+            .ok_or("'auth_url' is needed by authorization_code grant")?;
+
+        let mut params = query::parse_params(auth_url);
+        params.extend([
+            FormField::init("response_type", "code"),
+            FormField::init("client_id", &self.client_id),
+            FormField::init("redirect_uri", redirect_uri),
+            FormField::init("state", state),
+            FormField::init("code_challenge", &pkce.challenge),
+            FormField::init("code_challenge_method", "S256"),
+        ]);
+        params.extend(self.get_scope_fields());
+        Ok(query::set_params(auth_url, &params))
+    }
+
+    fn token_request(&self, fields: Vec<FormField>) -> PreparedRequest {
+        let mut request = Request::default();
+        request.set_method(METHODS::POST);
+        request.set_url(self.token_url.clone());
+        request.set_headers(HashMap::from([
+            (String::from("Content-Type"), FORM_CONTENT_TYPE.to_string()),
+            (String::from("Accept"), String::from("application/json")),
+        ]));
+        request.set_body(form::encode(&fields));
+        PreparedRequest::from(request)
+    }
+}
+
+// Proof Key for Code Exchange (RFC 7636), with S256 method
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pkce {
+    pub verifier: String,
+    pub challenge: String,
+}
+
+impl Pkce {
+    pub fn create() -> Self {
+        let verifier = format!(
+            "{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        );
+        Self::from_verifier(verifier)
+    }
+
+    pub fn from_verifier(verifier: String) -> Self {
+        let challenge = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .encode(Sha256::digest(verifier.as_bytes()));
+        Self {
+            verifier,
+            challenge,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OAuth2Token {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    // Unix time, None when the server doesn't say
+    pub expires_at: Option<i64>,
+}
+
+impl OAuth2Token {
+    /// Token in the body of a response of the token endpoint (RFC 6749, 5.1)
+    pub fn from_response(body: &str, now: i64) -> Result<Self, String> {
+        #[derive(Deserialize)]
+        struct TokenResponse {
+            access_token: String,
+            refresh_token: Option<String>,
+            expires_in: Option<i64>,
+        }
+
+        let response: TokenResponse = serde_json::from_str(body)
+            .map_err(|e| format!("Invalid response of token endpoint: {}", e))?;
+
+        Ok(Self {
+            access_token: response.access_token,
+            refresh_token: response.refresh_token,
+            expires_at: response.expires_in.map(|secs| now + secs),
+        })
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        match self.expires_at {
+            Some(expires_at) => expires_at - EXPIRATION_MARGIN_SECS <= now,
+            None => false,
+        }
+    }
+}
+
+// Tokens received of OAuth2 servers, kept until they expire.
+// Every change is saved in 'oauth_tokens.json' of the data folder
+pub struct TokenCache {
+    tokens: Mutex<HashMap<String, OAuth2Token>>,
+    file: Option<Mutex<DataFile>>,
+}
+
+impl TokenCache {
+    pub fn init(tokens: HashMap<String, OAuth2Token>) -> Self {
+        Self {
+            tokens: Mutex::new(tokens),
+            file: None,
+        }
+    }
+
+    pub fn setup_and_init() -> Result<Self, String> {
+        DataFile::setup()?;
+        let file = DataFile::from_name(String::from("oauth_tokens.json"));
+
+        let tokens = match file.get_content() {
+            Ok(content) => serde_json::from_str(&content).map_err(|e| e.to_string())?,
+            Err(_) => HashMap::new(),
+        };
+
+        Ok(Self {
+            tokens: Mutex::new(tokens),
+            file: Some(Mutex::new(file)),
+        })
+    }
+
+    pub fn now() -> i64 {
+        OffsetDateTime::now_utc().unix_timestamp()
+    }
+
+    pub fn get(&self, key: &str) -> Option<OAuth2Token> {
+        self.tokens.lock().unwrap().get(key).cloned()
+    }
+
+    pub fn set(&self, key: String, token: OAuth2Token) -> Result<(), String> {
+        let mut tokens = self.tokens.lock().unwrap();
+        let now = Self::now();
+        tokens.retain(|_, token| !token.is_expired(now) || token.refresh_token.is_some());
+        tokens.insert(key, token);
+
+        if let Some(file) = &self.file {
+            let content = serde_json::to_string_pretty(&*tokens).map_err(|e| e.to_string())?;
+            file.lock().unwrap().save_content(content)?;
+        }
+        Ok(())
+    }
+}
+
+// Listener in localhost receiving the redirect of the browser, after the user allows it
+pub struct RedirectListener {
+    listener: TcpListener,
+}
+
+impl RedirectListener {
+    pub async fn bind(port: Option<u16>) -> Result<Self, String> {
+        let listener = TcpListener::bind(("127.0.0.1", port.unwrap_or(0)))
+            .await
+            .map_err(|e| format!("Redirect listener: {}", e))?;
+        Ok(Self { listener })
+    }
+
+    pub fn get_redirect_uri(&self) -> Result<String, String> {
+        let port = self
+            .listener
+            .local_addr()
+            .map_err(|e| e.to_string())?
+            .port();
+        Ok(format!("http://127.0.0.1:{}/callback", port))
+    }
+
+    /// Code of the first redirect with `state`, the others are ignored
+    pub async fn wait_code(&self, state: &str) -> Result<String, String> {
+        tokio::time::timeout(AUTHORIZATION_TIMEOUT, self.accept_code(state))
+            .await
+            .map_err(|_| String::from("Authorization was not done in time"))?
+    }
+
+    async fn accept_code(&self, state: &str) -> Result<String, String> {
+        loop {
+            let (mut stream, _) = self.listener.accept().await.map_err(|e| e.to_string())?;
+
+            let head = Self::read_request_line(&mut stream).await?;
+
+            // Request line is like 'GET /callback?code=...&state=... HTTP/1.1'
+            let target = head.split_whitespace().nth(1).unwrap_or_default();
+            let params: HashMap<String, String> = query::parse_params(target)
+                .into_iter()
+                .map(|param| (param.key, param.value))
+                .collect();
+
+            if params.get("state").map(String::as_str) != Some(state) {
+                let _ = Self::reply(&mut stream, "Unknown authorization").await;
+                continue;
+            }
+
+            let result = match (params.get("code"), params.get("error")) {
+                (Some(code), _) => Ok(code.clone()),
+                (None, Some(error)) => Err(format!("Authorization denied: {}", error)),
+                (None, None) => Err(String::from("Redirect without code")),
+            };
+            let message = match &result {
+                Ok(_) => "Authorized, you can go back to TReq",
+                Err(e) => e.as_str(),
+            };
+            let _ = Self::reply(&mut stream, message).await;
+            return result;
+        }
+    }
+
+    // A request line may come in many reads, the state must be read whole
+    async fn read_request_line(stream: &mut tokio::net::TcpStream) -> Result<String, String> {
+        let mut head = vec![];
+        let mut buffer = [0; 1024];
+
+        while !head.contains(&b'\n') && head.len() < 8192 {
+            let size = stream.read(&mut buffer).await.map_err(|e| e.to_string())?;
+            if size == 0 {
+                break;
+            }
+            head.extend_from_slice(&buffer[..size]);
+        }
+
+        Ok(String::from_utf8_lossy(&head).to_string())
+    }
+
+    async fn reply(stream: &mut tokio::net::TcpStream, message: &str) -> std::io::Result<()> {
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            message.len(),
+            message
+        );
+        stream.write_all(response.as_bytes()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> OAuth2Config {
+        OAuth2Config {
+            grant: OAuth2Grant::AuthorizationCode,
+            token_url: String::from("http://auth.local/token"),
+            auth_url: Some(String::from("http://auth.local/authorize?audience=api")),
+            client_id: String::from("treq"),
+            client_secret: String::new(),
+            scope: Some(String::from("read write")),
+            redirect_port: None,
+        }
+    }
+
+    // Example of RFC 7636, appendix B
+    #[test]
+    fn should_create_pkce_challenge() {
+        let pkce = Pkce::from_verifier(String::from("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"));
+        assert_eq!(
+            pkce.challenge,
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+        assert_eq!(Pkce::create().verifier.len(), 64);
+    }
+
+    #[test]
+    fn should_build_authorization_url() {
+        let pkce = Pkce::from_verifier(String::from("verifier"));
+
+        let url = config()
+            .authorization_url("http://127.0.0.1:9000/callback", "xyz", &pkce)
+            .unwrap();
+
+        assert_eq!(
+            url,
+            format!(
+                "http://auth.local/authorize?audience=api&response_type=code&client_id=treq\
+                 &redirect_uri=http%3A%2F%2F127.0.0.1%3A9000%2Fcallback&state=xyz\
+                 &code_challenge={}&code_challenge_method=S256&scope=read%20write",
+                pkce.challenge
+            )
+        );
+    }
+
+    #[test]
+    fn should_not_share_tokens_of_other_grant_or_secret() {
+        let key = config().get_cache_key();
+        let other_grant = OAuth2Config {
+            grant: OAuth2Grant::ClientCredentials,
+            ..config()
+        };
+        let other_secret = OAuth2Config {
+            client_secret: String::from("s3cret"),
+            ..config()
+        };
+
+        assert_ne!(other_grant.get_cache_key(), key);
+        assert_ne!(other_secret.get_cache_key(), key);
+        assert!(!other_secret.get_cache_key().contains("s3cret"));
+        assert_eq!(config().get_cache_key(), key);
+    }
+
+    #[test]
+    fn should_build_token_requests_as_forms() {
+        let config = OAuth2Config {
+            client_secret: String::from("s3cret"),
+            ..config()
+        };
+
+        let request = config.client_credentials_request();
+        assert_eq!(request.method, METHODS::POST);
+        assert_eq!(request.url, "http://auth.local/token");
+        assert_eq!(request.headers["Content-Type"], FORM_CONTENT_TYPE);
+        assert_eq!(
+            request.body,
+            "grant_type=client_credentials&scope=read+write&client_id=treq&client_secret=s3cret"
+        );
+
+        let request = config.refresh_request("r1");
+        assert_eq!(
+            request.body,
+            "grant_type=refresh_token&refresh_token=r1&scope=read+write&client_id=treq&client_secret=s3cret"
+        );
+    }
+
+    #[test]
+    fn should_read_token_of_response() {
+        let token = OAuth2Token::from_response(
+            r#"{ "access_token": "abc", "token_type": "Bearer", "expires_in": 3600, "refresh_token": "r1" }"#,
+            1000,
+        )
+        .unwrap();
+
+        assert_eq!(token.access_token, "abc");
+        assert_eq!(token.refresh_token.as_deref(), Some("r1"));
+        assert_eq!(token.expires_at, Some(4600));
+        assert!(!token.is_expired(4500));
+        assert!(token.is_expired(4580));
+
+        assert!(OAuth2Token::from_response(r#"{ "error": "invalid_client" }"#, 0).is_err());
+    }
+
+    #[tokio::test]
+    async fn should_receive_code_of_redirect() {
+        let listener = RedirectListener::bind(None).await.unwrap();
+        let redirect_uri = listener.get_redirect_uri().unwrap();
+        let addr = redirect_uri
+            .trim_start_matches("http://")
+            .trim_end_matches("/callback")
+            .to_string();
+
+        let browser = tokio::spawn(async move {
+            for target in [
+                "/callback?code=old&state=other",
+                "/callback?code=c0de&state=xyz",
+            ] {
+                let mut stream = tokio::net::TcpStream::connect(&addr).await.unwrap();
+                let request = format!("GET {} HTTP/1.1\r\nHost: {}\r\n\r\n", target, addr);
+                stream.write_all(request.as_bytes()).await.unwrap();
+                let mut response = String::new();
+                stream.read_to_string(&mut response).await.unwrap();
+            }
+        });
+
+        assert_eq!(listener.wait_code("xyz").await, Ok(String::from("c0de")));
+        browser.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_receive_code_of_redirect_sent_in_pieces() {
+        let listener = RedirectListener::bind(None).await.unwrap();
+        let redirect_uri = listener.get_redirect_uri().unwrap();
+        let addr = redirect_uri
+            .trim_start_matches("http://")
+            .trim_end_matches("/callback")
+            .to_string();
+
+        let browser = tokio::spawn(async move {
+            let mut stream = tokio::net::TcpStream::connect(&addr).await.unwrap();
+            stream
+                .write_all(b"GET /callback?code=c0de&st")
+                .await
+                .unwrap();
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            stream.write_all(b"ate=xyz HTTP/1.1\r\n\r\n").await.unwrap();
+            stream.read_to_string(&mut String::new()).await.unwrap();
+        });
+
+        assert_eq!(listener.wait_code("xyz").await, Ok(String::from("c0de")));
+        browser.await.unwrap();
+    }
+}
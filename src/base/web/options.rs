@@ -0,0 +1,258 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+static DEFAULT_RETRY_BACKOFF_MS: u64 = 500;
+static DEFAULT_MAX_REDIRECTS: u32 = 10;
+
+// Protocol asked to the server. Auto lets them agree on it, offering HTTP/2 by ALPN in
+// https. Http2 is sent without asking (prior knowledge), H2 needs the server to accept
+// it by ALPN
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum HttpVersion {
+    #[default]
+    Auto,
+    Http1,
+    Http2,
+    H2,
+}
+
+// Options of how a Request is sent. Each one not set in the Request uses the value of
+// [request] table in config.toml
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RequestOptions {
+    pub connect_timeout_ms: Option<u64>,
+    pub timeout_ms: Option<u64>,
+
+    // Retries after the first attempt, when it fails or its status is in 'retry_on_status'.
+    // The wait between them starts at 'retry_backoff_ms' and doubles each attempt
+    pub retries: Option<u32>,
+    pub retry_on_status: Option<Vec<u16>>,
+    pub retry_backoff_ms: Option<u64>,
+
+    // Redirects are followed by default, until 'max_redirects' hops
+    pub follow_redirects: Option<bool>,
+    pub max_redirects: Option<u32>,
+
+    // Without 'proxy' the HTTP_PROXY, HTTPS_PROXY and ALL_PROXY environment variables are
+    // used, "none" disables them. 'no_proxy' are hosts reached without it, as NO_PROXY
+    pub proxy: Option<String>,
+    pub no_proxy: Option<String>,
+
+    // Paths of PEM files, 'client_cert' may also be a PKCS#12 (.p12 or .pfx) one and
+    // 'client_key' must be PKCS#8. 'min_tls_version' goes up to 1.2, and 'insecure'
+    // accepts any certificate of the server
+    pub ca_cert: Option<String>,
+    pub client_cert: Option<String>,
+    pub client_key: Option<String>,
+    pub client_cert_password: Option<String>,
+    pub min_tls_version: Option<String>,
+    pub insecure: Option<bool>,
+
+    // WebSocket tabs send the body as a binary frame, written in hex
+    pub websocket_binary: Option<bool>,
+
+    // gRPC tabs read the methods of these .proto (compiled by protoc) or descriptor set
+    // files, instead of asking the server reflection
+    pub proto_files: Option<Vec<String>>,
+
+    // Body sent compressed with gzip, for servers that accept 'Content-Encoding: gzip'
+    pub gzip_body: Option<bool>,
+
+    // "auto", "http1", "http2" (prior knowledge) or "h2" (by ALPN)
+    pub http_version: Option<String>,
+
+    // Path of a Unix socket where the request is sent, the host of the URL is only its
+    // Host header. URLs as "unix:///path/to.sock:/v1/items" set it too
+    pub unix_socket: Option<String>,
+}
+
+impl RequestOptions {
+    pub fn with_defaults(&self, defaults: &RequestOptions) -> RequestOptions {
+        RequestOptions {
+            connect_timeout_ms: self.connect_timeout_ms.or(defaults.connect_timeout_ms),
+            timeout_ms: self.timeout_ms.or(defaults.timeout_ms),
+            retries: self.retries.or(defaults.retries),
+            retry_on_status: self
+                .retry_on_status
+                .clone()
+                .or_else(|| defaults.retry_on_status.clone()),
+            retry_backoff_ms: self.retry_backoff_ms.or(defaults.retry_backoff_ms),
+            follow_redirects: self.follow_redirects.or(defaults.follow_redirects),
+            max_redirects: self.max_redirects.or(defaults.max_redirects),
+            proxy: self.proxy.clone().or_else(|| defaults.proxy.clone()),
+            no_proxy: self.no_proxy.clone().or_else(|| defaults.no_proxy.clone()),
+            ca_cert: self.ca_cert.clone().or_else(|| defaults.ca_cert.clone()),
+            client_cert: self.client_cert.clone().or_else(|| defaults.client_cert.clone()),
+            client_key: self.client_key.clone().or_else(|| defaults.client_key.clone()),
+            client_cert_password: self
+                .client_cert_password
+                .clone()
+                .or_else(|| defaults.client_cert_password.clone()),
+            min_tls_version: self
+                .min_tls_version
+                .clone()
+                .or_else(|| defaults.min_tls_version.clone()),
+            insecure: self.insecure.or(defaults.insecure),
+            websocket_binary: self.websocket_binary.or(defaults.websocket_binary),
+            proto_files: self
+                .proto_files
+                .clone()
+                .or_else(|| defaults.proto_files.clone()),
+            gzip_body: self.gzip_body.or(defaults.gzip_body),
+            http_version: self
+                .http_version
+                .clone()
+                .or_else(|| defaults.http_version.clone()),
+            unix_socket: self
+                .unix_socket
+                .clone()
+                .or_else(|| defaults.unix_socket.clone()),
+        }
+    }
+
+    pub fn get_connect_timeout(&self) -> Option<Duration> {
+        self.connect_timeout_ms.map(Duration::from_millis)
+    }
+
+    pub fn get_timeout(&self) -> Option<Duration> {
+        self.timeout_ms.map(Duration::from_millis)
+    }
+
+    pub fn get_retries(&self) -> u32 {
+        self.retries.unwrap_or(0)
+    }
+
+    pub fn should_retry_status(&self, status: i32) -> bool {
+        match &self.retry_on_status {
+            Some(statuses) => statuses.iter().any(|s| i32::from(*s) == status),
+            None => false,
+        }
+    }
+
+    pub fn is_insecure(&self) -> bool {
+        self.insecure.unwrap_or(false)
+    }
+
+    pub fn should_gzip_body(&self) -> bool {
+        self.gzip_body.unwrap_or(false)
+    }
+
+    pub fn get_http_version(&self) -> Result<HttpVersion, String> {
+        let version = self.http_version.as_ref().map(|v| v.to_lowercase());
+        match version.as_deref() {
+            None | Some("auto") => Ok(HttpVersion::Auto),
+            Some("http1" | "http1.1") => Ok(HttpVersion::Http1),
+            Some("http2") => Ok(HttpVersion::Http2),
+            Some("h2") => Ok(HttpVersion::H2),
+            Some(version) => Err(format!(
+                "{}, use \"auto\", \"http1\", \"http2\" or \"h2\"",
+                version
+            )),
+        }
+    }
+
+    pub fn is_websocket_binary(&self) -> bool {
+        self.websocket_binary.unwrap_or(false)
+    }
+
+    pub fn should_follow_redirect(&self, hops_done: usize) -> bool {
+        let max_redirects = self.max_redirects.unwrap_or(DEFAULT_MAX_REDIRECTS) as usize;
+        self.follow_redirects.unwrap_or(true) && hops_done < max_redirects
+    }
+
+    /// Time to wait before the retry number `attempt` (starting at 1)
+    pub fn get_backoff(&self, attempt: u32) -> Duration {
+        let base = self.retry_backoff_ms.unwrap_or(DEFAULT_RETRY_BACKOFF_MS);
+        let factor = 2_u64.saturating_pow(attempt.saturating_sub(1));
+        Duration::from_millis(base.saturating_mul(factor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_use_defaults_only_for_options_not_set() {
+        let defaults = RequestOptions {
+            connect_timeout_ms: Some(1000),
+            timeout_ms: Some(30000),
+            retries: Some(2),
+            retry_on_status: Some(vec![503]),
+            retry_backoff_ms: None,
+            follow_redirects: Some(true),
+            max_redirects: None,
+            proxy: Some("http://proxy:3128".to_string()),
+            insecure: Some(true),
+            ..RequestOptions::default()
+        };
+        let options = RequestOptions {
+            timeout_ms: Some(5000),
+            insecure: Some(false),
+            retry_on_status: Some(vec![502, 504]),
+            ..RequestOptions::default()
+        };
+
+        let final_options = options.with_defaults(&defaults);
+
+        assert_eq!(final_options.get_connect_timeout(), Some(Duration::from_secs(1)));
+        assert_eq!(final_options.get_timeout(), Some(Duration::from_secs(5)));
+        assert_eq!(final_options.get_retries(), 2);
+        assert!(final_options.should_retry_status(502));
+        assert!(!final_options.should_retry_status(503));
+        assert_eq!(final_options.proxy, Some("http://proxy:3128".to_string()));
+        assert!(!final_options.is_insecure());
+    }
+
+    #[test]
+    fn should_double_backoff_each_attempt() {
+        let options = RequestOptions {
+            retry_backoff_ms: Some(100),
+            ..RequestOptions::default()
+        };
+
+        assert_eq!(options.get_backoff(1), Duration::from_millis(100));
+        assert_eq!(options.get_backoff(2), Duration::from_millis(200));
+        assert_eq!(options.get_backoff(4), Duration::from_millis(800));
+        assert_eq!(
+            RequestOptions::default().get_backoff(1),
+            Duration::from_millis(DEFAULT_RETRY_BACKOFF_MS)
+        );
+    }
+
+    #[test]
+    fn should_follow_redirects_until_max() {
+        let default_options = RequestOptions::default();
+        assert!(default_options.should_follow_redirect(0));
+        assert!(default_options.should_follow_redirect(9));
+        assert!(!default_options.should_follow_redirect(10));
+
+        let options = RequestOptions {
+            max_redirects: Some(2),
+            ..RequestOptions::default()
+        };
+        assert!(options.should_follow_redirect(1));
+        assert!(!options.should_follow_redirect(2));
+
+        let options = RequestOptions {
+            follow_redirects: Some(false),
+            ..RequestOptions::default()
+        };
+        assert!(!options.should_follow_redirect(0));
+    }
+
+    #[test]
+    fn should_parse_http_version() {
+        let with_version = |version: &str| RequestOptions {
+            http_version: Some(version.to_string()),
+            ..RequestOptions::default()
+        };
+
+        assert_eq!(RequestOptions::default().get_http_version(), Ok(HttpVersion::Auto));
+        assert_eq!(with_version("HTTP1.1").get_http_version(), Ok(HttpVersion::Http1));
+        assert_eq!(with_version("http2").get_http_version(), Ok(HttpVersion::Http2));
+        assert_eq!(with_version("h2").get_http_version(), Ok(HttpVersion::H2));
+        assert!(with_version("http3").get_http_version().is_err());
+    }
+}
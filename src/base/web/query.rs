@@ -0,0 +1,205 @@
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+
+use super::form::{self, FormField};
+
+// A query is encoded as a form, so each param is a field of it
+pub type QueryParam = FormField;
+
+fn default_position() -> usize {
+    usize::MAX
+}
+
+// A param out of the URL, with its index among all the params to show it where it was.
+// Saved without a position, it goes after the others
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisabledParam {
+    #[serde(flatten)]
+    pub param: QueryParam,
+    #[serde(default = "default_position")]
+    pub position: usize,
+}
+
+// Everything but the unreserved characters, so a space is %20 and not the '+' of forms
+const COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+// Parts of a URL around its query: what is before the '?' and the fragment with its '#'
+fn split_url(url: &str) -> (&str, Option<&str>, &str) {
+    let (without_fragment, fragment) = match url.find('#') {
+        Some(i) => url.split_at(i),
+        None => (url, ""),
+    };
+
+    match without_fragment.split_once('?') {
+        Some((base, query)) => (base, Some(query), fragment),
+        None => (without_fragment, None, fragment),
+    }
+}
+
+/// `url` without its query and fragment, what tells the endpoint requested
+pub fn get_endpoint(url: &str) -> &str {
+    split_url(url).0
+}
+
+/// Params in the query of `url`, decoded. All of them are enabled, the disabled ones are
+/// not in the URL
+pub fn parse_params(url: &str) -> Vec<QueryParam> {
+    let (_, query, _) = split_url(url);
+
+    form_urlencoded::parse(query.unwrap_or("").as_bytes())
+        .map(|(key, value)| QueryParam {
+            key: key.to_string(),
+            value: value.to_string(),
+            enabled: true,
+        })
+        .collect()
+}
+
+/// `url` with its query made of the enabled `params`. The ones already in the query keep
+/// how they were written, like a `+` or a `?flag`, only the new or edited are encoded
+pub fn set_params(url: &str, params: &[QueryParam]) -> String {
+    let (base, query, fragment) = split_url(url);
+    let mut written: Vec<&str> = query
+        .unwrap_or("")
+        .split('&')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    let mut segments = vec![];
+    for param in params.iter().filter(|param| param.enabled) {
+        let segment = match written.iter().position(|s| is_written_as(param, s)) {
+            Some(i) => written.remove(i).to_string(),
+            None => format!(
+                "{}={}",
+                utf8_percent_encode(&param.key, COMPONENT),
+                utf8_percent_encode(&param.value, COMPONENT)
+            ),
+        };
+        segments.push(segment);
+    }
+    let query = segments.join("&");
+
+    match query.is_empty() {
+        true => format!("{}{}", base, fragment),
+        false => format!("{}?{}{}", base, query, fragment),
+    }
+}
+
+/// Enabled `params` with the `disabled` ones put back at their positions
+pub fn merge_params(mut params: Vec<QueryParam>, disabled: &[DisabledParam]) -> Vec<QueryParam> {
+    let mut disabled = disabled.to_vec();
+    disabled.sort_by_key(|disabled| disabled.position);
+
+    for disabled in disabled {
+        let position = disabled.position.min(params.len());
+        params.insert(position, disabled.param);
+    }
+    params
+}
+
+/// The disabled ones of `params`, each with its position
+pub fn split_disabled(params: &[QueryParam]) -> Vec<DisabledParam> {
+    params
+        .iter()
+        .enumerate()
+        .filter(|(_, param)| !param.enabled)
+        .map(|(position, param)| DisabledParam {
+            param: param.clone(),
+            position,
+        })
+        .collect()
+}
+
+fn is_written_as(param: &QueryParam, segment: &str) -> bool {
+    form_urlencoded::parse(segment.as_bytes())
+        .next()
+        .is_some_and(|(key, value)| key == param.key && value == param.value)
+}
+
+pub fn params_as_table(params: &[QueryParam]) -> String {
+    if params.is_empty() {
+        return String::from("No query params, press [e] to add them");
+    }
+
+    form::fields_as_table(params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_params_of_url() {
+        assert_eq!(
+            parse_params("https://api.com/search?q=rust%20lang&page=2&empty#results"),
+            vec![
+                QueryParam::init("q", "rust lang"),
+                QueryParam::init("page", "2"),
+                QueryParam::init("empty", "")
+            ]
+        );
+        assert_eq!(parse_params("localhost:8080/users"), vec![]);
+    }
+
+    #[test]
+    fn should_write_enabled_params_in_url() {
+        let params = vec![
+            QueryParam::init("q", "a&b"),
+            QueryParam {
+                enabled: false,
+                ..QueryParam::init("debug", "1")
+            },
+            QueryParam::init("page", "2"),
+        ];
+
+        assert_eq!(
+            set_params("https://api.com/search?old=1#top", &params),
+            "https://api.com/search?q=a%26b&page=2#top"
+        );
+        assert_eq!(
+            set_params("https://api.com/search?old=1", &[]),
+            "https://api.com/search"
+        );
+    }
+
+    #[test]
+    fn should_keep_params_as_written_in_url() {
+        let url = "https://api.com/search?q=rust+lang&flag&path=a%2Fb&page=2";
+        let mut params = parse_params(url);
+        params[3].value = String::from("3 of 4");
+        params.push(QueryParam::init("sort", "name"));
+
+        assert_eq!(
+            set_params(url, &params),
+            "https://api.com/search?q=rust+lang&flag&path=a%2Fb&page=3%20of%204&sort=name"
+        );
+    }
+
+    #[test]
+    fn should_put_disabled_params_back_at_their_positions() {
+        let disabled = |key: &str| QueryParam {
+            enabled: false,
+            ..QueryParam::init(key, "1")
+        };
+        let params = vec![
+            disabled("a"),
+            QueryParam::init("b", "1"),
+            disabled("c"),
+            QueryParam::init("d", "1"),
+        ];
+
+        let enabled = params.iter().filter(|p| p.enabled).cloned().collect();
+        assert_eq!(merge_params(enabled, &split_disabled(&params)), params);
+
+        let old = DisabledParam {
+            param: disabled("old"),
+            position: default_position(),
+        };
+        let merged = merge_params(vec![QueryParam::init("b", "1")], &[old]);
+        assert_eq!(merged[1].key, "old");
+    }
+}
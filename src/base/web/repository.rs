@@ -1,43 +1,337 @@
-pub mod reqwest;
+use super::options::RequestOptions;
+use super::request::{Request, METHODS};
 use super::response::Response;
+use super::sse::ServerSentEvent;
+use super::websocket::{WebSocketConnection, WebSocketListener};
 use async_trait::async_trait;
+use prost_reflect::DescriptorPool;
 use std::collections::HashMap;
+use std::error::Error;
+use std::{fmt, io};
+
+pub mod reqwest;
+mod tls;
+pub mod tonic;
+pub mod tungstenite;
+
+// Everything a HttpClientRepository needs to send a Request, after all validators
+// were applied to it
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreparedRequest {
+    pub method: METHODS,
+    pub url: String,
+    pub headers: HashMap<String, String>,
+    pub body: String,
+    // Bytes sent instead of 'body', for the ones that are not text like multipart
+    pub raw_body: Option<Vec<u8>>,
+    pub options: RequestOptions,
+}
+
+impl From<Request> for PreparedRequest {
+    fn from(request: Request) -> Self {
+        Self {
+            method: request.method,
+            url: request.url,
+            headers: request.headers,
+            body: request.body,
+            raw_body: None,
+            options: request.options,
+        }
+    }
+}
+
+// Why a Request got no Response. They are kept apart of Response, so any status code
+// sent by a server is shown as it is
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HttpError {
+    InvalidMethod(String),
+    InvalidUrl(String),
+    InvalidHeader(String),
+    InvalidProxy(String),
+    InvalidTls(String),
+    InvalidHttpVersion(String),
+    InvalidDescriptor(String),
+    InvalidMessage(String),
+    // The Request could not be built, like a missing file or a failed signature
+    InvalidRequest(String),
+    // The auth could not be done, like an OAuth2 token or a Digest challenge that failed
+    Auth(String),
+    Dns(String),
+    ConnectionRefused(String),
+    Tls(String),
+    Timeout(String),
+    Transport(String),
+    Body(String),
+}
+
+impl HttpError {
+    // Errors of the connection may not happen again, the others surely will
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::Dns(_)
+                | Self::ConnectionRefused(_)
+                | Self::Timeout(_)
+                | Self::Transport(_)
+                | Self::Body(_)
+        )
+    }
+
+    /// Kind of failure of a connection, found in the chain of errors that caused it.
+    /// Clients only say the request failed, their sources say why. The types of the
+    /// sources are checked first, their text only when none of them tells the kind
+    pub fn from_connection_error(error: &(dyn Error + 'static)) -> Self {
+        let mut messages: Vec<String> = vec![];
+        let mut kind: Option<fn(String) -> Self> = None;
+        let mut source = Some(error);
+
+        while let Some(cause) = source {
+            let message = cause.to_string();
+            if !messages.contains(&message) {
+                messages.push(message);
+            }
+            kind = kind.or_else(|| Self::kind_of_cause(cause));
+            source = cause.source();
+        }
+
+        let detail = messages.join(": ");
+        if let Some(kind) = kind {
+            return kind(detail);
+        }
+
+        // The first message may have the URL, which says nothing of the failure
+        let causes = match messages.len() {
+            1 => detail.to_lowercase(),
+            _ => messages[1..].join(": ").to_lowercase(),
+        };
+
+        if causes.contains("dns error") || causes.contains("failed to lookup address") {
+            Self::Dns(detail)
+        } else if causes.contains("connection refused") {
+            Self::ConnectionRefused(detail)
+        } else if causes.contains("timed out") {
+            Self::Timeout(detail)
+        } else if causes.contains("certificate") {
+            // TLS libraries without a type here, like rustls of gRPC
+            Self::Tls(detail)
+        } else {
+            Self::Transport(detail)
+        }
+    }
+
+    fn kind_of_cause(cause: &(dyn Error + 'static)) -> Option<fn(String) -> Self> {
+        if cause.is::<native_tls::Error>() {
+            return Some(Self::Tls);
+        }
+        let timed_out = cause
+            .downcast_ref::<::reqwest::Error>()
+            .is_some_and(::reqwest::Error::is_timeout)
+            || cause
+                .downcast_ref::<hyper::Error>()
+                .is_some_and(hyper::Error::is_timeout);
+        if timed_out {
+            return Some(Self::Timeout);
+        }
+
+        // io::Error hides the error it wraps from the chain of sources
+        let io_error = cause.downcast_ref::<io::Error>()?;
+        match io_error.kind() {
+            io::ErrorKind::ConnectionRefused => Some(Self::ConnectionRefused),
+            io::ErrorKind::TimedOut => Some(Self::Timeout),
+            _ => Self::kind_of_cause(io_error.get_ref()?),
+        }
+    }
+
+    pub fn get_title(&self) -> &'static str {
+        match self {
+            Self::InvalidMethod(_) => "INVALID METHOD",
+            Self::InvalidUrl(_) => "INVALID URL",
+            Self::InvalidHeader(_) => "INVALID HEADER",
+            Self::InvalidProxy(_) => "INVALID PROXY",
+            Self::InvalidTls(_) => "INVALID TLS OPTIONS",
+            Self::InvalidHttpVersion(_) => "INVALID HTTP VERSION",
+            Self::InvalidDescriptor(_) => "INVALID DESCRIPTORS",
+            Self::InvalidMessage(_) => "INVALID MESSAGE",
+            Self::InvalidRequest(_) => "INVALID REQUEST",
+            Self::Auth(_) => "AUTH FAILED",
+            Self::Dns(_) => "DNS FAILURE",
+            Self::ConnectionRefused(_) => "CONNECTION REFUSED",
+            Self::Tls(_) => "TLS ERROR",
+            Self::Timeout(_) => "TIMEOUT",
+            Self::Transport(_) => "CONNECTION ERROR",
+            Self::Body(_) => "BODY ERROR",
+        }
+    }
+
+    /// Likely cause of the error, and what to check to solve it
+    pub fn get_hint(&self) -> &'static str {
+        match self {
+            Self::InvalidMethod(_) => "The method must be a single word, as GET or POST",
+            Self::InvalidUrl(_) => "Check the scheme, host and port of the URL",
+            Self::InvalidHeader(_) => {
+                "Names of headers can't have spaces, and values can't have line breaks"
+            }
+            Self::InvalidProxy(_) => {
+                "Write the proxy as http://host:port, https://, socks5:// or socks5h://"
+            }
+            Self::InvalidTls(_) => "Check the paths and the password of the certificate options",
+            Self::InvalidHttpVersion(_) => "Use \"auto\" to let the server choose the protocol",
+            Self::InvalidDescriptor(_) => {
+                "Check 'proto_files', or if the server has reflection enabled"
+            }
+            Self::InvalidMessage(_) => "The body must be JSON with the fields of the method input",
+            Self::InvalidRequest(_) => "Check the body, the files and the auth of the request",
+            Self::Auth(_) => "Check the credentials of the auth, and the URLs of an OAuth2 one",
+            Self::Dns(_) => "The host doesn't exist or can't be resolved, check its spelling",
+            Self::ConnectionRefused(_) => {
+                "Nothing is listening in this host and port, is the server running?"
+            }
+            Self::Tls(_) => {
+                "The certificate is not trusted. Set 'ca_cert', or 'insecure' to skip checking it"
+            }
+            Self::Timeout(_) => {
+                "The server took too long, raise 'timeout_ms' or 'connect_timeout_ms'"
+            }
+            Self::Transport(_) => "The connection failed, check the network and the proxy",
+            Self::Body(_) => "The connection was closed, or the body came wrongly encoded",
+        }
+    }
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidMethod(e) => write!(f, "Invalid method: {}", e),
+            Self::InvalidUrl(e) => write!(f, "Invalid URL: {}", e),
+            Self::InvalidHeader(e) => write!(f, "Invalid header: {}", e),
+            Self::InvalidProxy(e) => write!(f, "Invalid proxy: {}", e),
+            Self::InvalidTls(e) => write!(f, "Invalid TLS options: {}", e),
+            Self::InvalidHttpVersion(e) => write!(f, "Invalid HTTP version: {}", e),
+            Self::InvalidDescriptor(e) => write!(f, "Invalid descriptors: {}", e),
+            Self::InvalidMessage(e) => write!(f, "Invalid message: {}", e),
+            Self::InvalidRequest(e) => write!(f, "{}", e),
+            Self::Auth(e) => write!(f, "Auth failed: {}", e),
+            Self::Dns(e) => write!(f, "DNS lookup failed: {}", e),
+            Self::ConnectionRefused(e) => write!(f, "Connection refused: {}", e),
+            Self::Tls(e) => write!(f, "TLS handshake failed: {}", e),
+            Self::Timeout(e) => write!(f, "Timed out: {}", e),
+            Self::Transport(e) => write!(f, "{}", e),
+            Self::Body(e) => write!(f, "Error reading body: {}", e),
+        }
+    }
+}
+
+// Called with each event of a 'text/event-stream' response, as soon as it arrives
+pub type EventListener = dyn Fn(ServerSentEvent) + Send + Sync;
 
 #[async_trait]
 pub trait HttpClientRepository {
-    async fn call_get(
-        &self,
-        url: String,
-        headers: HashMap<String, String>,
-    ) -> Result<Response, String>;
-    async fn call_post(
+    async fn execute(
         &self,
-        url: String,
-        headers: HashMap<String, String>,
-        body: String,
-    ) -> Result<Response, String>;
-    async fn call_delete(
-        &self,
-        url: String,
-        headers: HashMap<String, String>,
-        body: String,
-    ) -> Result<Response, String>;
-    async fn call_patch(
-        &self,
-        url: String,
-        headers: HashMap<String, String>,
-        body: String,
-    ) -> Result<Response, String>;
-    async fn call_put(
+        request: PreparedRequest,
+        on_event: &EventListener,
+    ) -> Result<Response, HttpError>;
+}
+
+// Transport of connections kept open, like WebSockets. The connection runs by itself
+// and everything that happens in it is given to 'on_event'
+pub trait SocketClientRepository {
+    fn connect(
         &self,
-        url: String,
-        headers: HashMap<String, String>,
-        body: String,
-    ) -> Result<Response, String>;
-    async fn call_head(
+        request: PreparedRequest,
+        on_event: Box<WebSocketListener>,
+    ) -> WebSocketConnection;
+}
+
+// Transport of gRPC calls. The methods of a server are known by its reflection, or by
+// the files in 'proto_files' option
+#[async_trait]
+pub trait GrpcClientRepository {
+    async fn fetch_descriptors(
         &self,
-        url: String,
-        headers: HashMap<String, String>,
-        body: String,
-    ) -> Result<Response, String>;
+        request: PreparedRequest,
+    ) -> Result<DescriptorPool, HttpError>;
+
+    async fn execute(&self, request: PreparedRequest) -> Result<Response, HttpError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Error of a client, that only says the request failed
+    #[derive(Debug)]
+    struct RequestFailed(Box<dyn Error + 'static>);
+
+    impl fmt::Display for RequestFailed {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "error sending request for url (https://tls.example.com)")
+        }
+    }
+
+    impl Error for RequestFailed {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(self.0.as_ref())
+        }
+    }
+
+    fn failed_by(cause: Box<dyn Error + 'static>) -> HttpError {
+        HttpError::from_connection_error(&RequestFailed(cause))
+    }
+
+    fn tls_error() -> native_tls::Error {
+        native_tls::Identity::from_pkcs8(b"no cert", b"no key")
+            .err()
+            .unwrap()
+    }
+
+    #[test]
+    fn should_find_kind_of_connection_error_in_its_causes() {
+        let refused = io::Error::new(io::ErrorKind::ConnectionRefused, "refused");
+        assert_eq!(
+            failed_by(Box::new(refused)),
+            HttpError::ConnectionRefused(String::from(
+                "error sending request for url (https://tls.example.com): refused"
+            ))
+        );
+
+        let dns = io::Error::other("dns error: failed to lookup address");
+        assert!(matches!(failed_by(Box::new(dns)), HttpError::Dns(_)));
+
+        let tls = io::Error::other("certificate verify failed");
+        assert!(matches!(failed_by(Box::new(tls)), HttpError::Tls(_)));
+
+        let tls = tls_error();
+        assert!(matches!(failed_by(Box::new(tls)), HttpError::Tls(_)));
+        let wrapped = io::Error::other(tls_error());
+        assert!(matches!(failed_by(Box::new(wrapped)), HttpError::Tls(_)));
+
+        // Words of TLS in other errors don't make them TLS errors
+        let proxy = io::Error::other("proxy handshake failed, ssl tunnel refused by proxy");
+        assert!(matches!(
+            failed_by(Box::new(proxy)),
+            HttpError::Transport(_)
+        ));
+
+        let timeout = io::Error::new(io::ErrorKind::TimedOut, "deadline");
+        assert!(matches!(
+            failed_by(Box::new(timeout)),
+            HttpError::Timeout(_)
+        ));
+
+        // The URL of the first message is not a cause
+        let reset = io::Error::new(io::ErrorKind::ConnectionReset, "connection reset");
+        assert!(matches!(
+            failed_by(Box::new(reset)),
+            HttpError::Transport(_)
+        ));
+    }
+
+    #[test]
+    fn should_retry_only_errors_of_connection() {
+        assert!(HttpError::ConnectionRefused(String::new()).is_retryable());
+        assert!(HttpError::Timeout(String::new()).is_retryable());
+        assert!(!HttpError::Tls(String::new()).is_retryable());
+        assert!(!HttpError::InvalidHeader(String::new()).is_retryable());
+    }
 }
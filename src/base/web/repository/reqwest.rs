@@ -1,146 +1,672 @@
 use async_trait::async_trait;
-use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::header::{self, HeaderMap, HeaderName, HeaderValue};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use std::{collections::HashMap, str::FromStr};
 
-use crate::base::web::response::Response;
+use crate::base::web::body;
+use crate::base::web::compression::{self, ResponseCompression};
+use crate::base::web::cookies::CookieJar;
+use crate::base::web::options::{HttpVersion, RequestOptions};
+use crate::base::web::proxy::{self, NO_PROXY_VALUE};
+use crate::base::web::response::{RedirectHop, Response, ResponseStage, ResponseTime};
+use crate::config::configurations::web_client::WebClientConfig;
 
-use super::HttpClientRepository;
-use reqwest::Client;
+use super::tls::TlsOptions;
+use super::{EventListener, HttpClientRepository, HttpError, PreparedRequest};
+use crate::base::web::sse::{ServerSentEvent, SseParser, EVENT_STREAM_CONTENT_TYPE};
+use reqwest::{redirect, Client, Method, Proxy, RequestBuilder, Url, Version};
 
-#[derive(Default)]
-pub struct ReqwestClientRepository;
+#[cfg(unix)]
+mod unix_socket;
+
+static UNIX_SCHEME: &str = "unix://";
+
+// Options of a Request that reqwest only allows to set in the Client
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+struct ClientOptions {
+    connect_timeout: Option<Duration>,
+    proxy: Option<String>,
+    no_proxy: Option<String>,
+    tls: TlsOptions,
+    http_version: HttpVersion,
+}
+
+impl From<&RequestOptions> for ClientOptions {
+    fn from(options: &RequestOptions) -> Self {
+        Self {
+            connect_timeout: options.get_connect_timeout(),
+            proxy: options.proxy.clone(),
+            no_proxy: options.no_proxy.clone(),
+            tls: TlsOptions::from(options),
+            // An invalid version is refused by execute, before creating the Client
+            http_version: options.get_http_version().unwrap_or_default(),
+        }
+    }
+}
+
+// Clients are created once and shared by all requests, so connections are kept alive
+// and reused between submits (reqwest keeps the pool inside it). There is one Client
+// for each combination of ClientOptions used
+pub struct ReqwestClientRepository {
+    clients: Mutex<HashMap<ClientOptions, Client>>,
+    config: WebClientConfig,
+    cookies: Arc<CookieJar>,
+}
 impl ReqwestClientRepository {
-    fn create_header_map(map: HashMap<String, String>) -> HeaderMap {
+    pub fn init(config: &WebClientConfig, cookies: Arc<CookieJar>) -> Result<Self, String> {
+        let repository = Self {
+            clients: Mutex::new(HashMap::new()),
+            config: config.clone(),
+            cookies,
+        };
+
+        // Checks the configuration before the first request
+        repository
+            .get_client(&ClientOptions::default())
+            .map_err(|e| e.to_string())?;
+
+        Ok(repository)
+    }
+
+    fn get_client(&self, options: &ClientOptions) -> Result<Client, HttpError> {
+        let mut clients = self.clients.lock().unwrap();
+
+        if let Some(client) = clients.get(options) {
+            return Ok(client.clone());
+        }
+
+        let client = self.create_client(options)?;
+        clients.insert(options.clone(), client.clone());
+        Ok(client)
+    }
+
+    fn create_header_map(map: HashMap<String, String>) -> Result<HeaderMap, HttpError> {
         let mut headers = HeaderMap::new();
 
         for (key, value) in map.iter() {
-            headers.insert(
-                HeaderName::from_str(key).unwrap(),
-                HeaderValue::from_str(value).unwrap(),
-            );
+            let name = HeaderName::from_str(key)
+                .map_err(|e| HttpError::InvalidHeader(format!("{}: {}", key, e)))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|e| HttpError::InvalidHeader(format!("{}: {}", key, e)))?;
+            headers.insert(name, value);
         }
 
-        headers
+        Ok(headers)
     }
 
-    async fn convert_to_app_response(response: reqwest::Response) -> Result<Response, String> {
+    fn create_client(&self, options: &ClientOptions) -> Result<Client, HttpError> {
+        let config = &self.config;
+        let mut builder = Client::builder()
+            .cookie_provider(self.cookies.clone())
+            // Redirects are followed by execute, to keep each hop in the Response
+            .redirect(redirect::Policy::none())
+            .pool_idle_timeout(config.get_pool_idle_timeout())
+            .tcp_keepalive(config.get_tcp_keepalive());
+
+        if let Some(proxy) = options.proxy.as_ref().filter(|p| *p != NO_PROXY_VALUE) {
+            proxy::parse_proxy(proxy).map_err(HttpError::InvalidProxy)?;
+        }
+
+        // Replaces the proxies of reqwest, to respect NO_PROXY with any of them
+        let proxy_options = RequestOptions {
+            proxy: options.proxy.clone(),
+            no_proxy: options.no_proxy.clone(),
+            ..RequestOptions::default()
+        };
+        builder = builder.proxy(Proxy::custom(move |url| {
+            proxy::get_proxy_for(url, &proxy_options)
+        }));
+
+        if let Some(connect_timeout) = options.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+
+        if let Some(max_idle) = config.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max_idle);
+        }
+
+        // H2 offers both protocols by ALPN as Auto does, the one agreed is checked later
+        builder = match options.http_version {
+            HttpVersion::Http1 => builder.http1_only(),
+            HttpVersion::Http2 => builder.http2_prior_knowledge(),
+            HttpVersion::Auto if config.http2_prior_knowledge => builder.http2_prior_knowledge(),
+            HttpVersion::Auto | HttpVersion::H2 => builder,
+        };
+
+        builder = options.tls.apply(builder)?;
+
+        builder
+            .build()
+            .map_err(|e| HttpError::Transport(e.to_string()))
+    }
+
+    async fn send(
+        &self,
+        request: RequestBuilder,
+        socket: Option<&str>,
+        deadline: Option<Instant>,
+        on_event: &EventListener,
+    ) -> Result<Response, HttpError> {
+        let started = Instant::now();
+
+        let response = ReqwestClientRepository::until(deadline, async {
+            match socket {
+                #[cfg(unix)]
+                Some(socket) => {
+                    let request = request
+                        .build()
+                        .map_err(|e| HttpError::InvalidRequest(e.to_string()))?;
+                    unix_socket::send(socket, request).await
+                }
+                #[cfg(not(unix))]
+                Some(socket) => Err(HttpError::InvalidRequest(format!(
+                    "{}: Unix sockets are not supported in this system",
+                    socket
+                ))),
+                None => request
+                    .send()
+                    .await
+                    .map_err(|e| HttpError::from_connection_error(&e)),
+            }
+        })
+        .await?;
+        let ttfb = started.elapsed();
+
+        let mut app_response =
+            ReqwestClientRepository::convert_to_app_response(response, deadline, on_event).await?;
+
+        let total = started.elapsed();
+        app_response.response_time = ResponseTime {
+            total,
+            ttfb,
+            download: total - ttfb,
+        };
+
+        Ok(app_response)
+    }
+
+    // The timeout of a Request is kept by this, as reqwest's one would also end streams
+    async fn until<T, F>(deadline: Option<Instant>, future: F) -> Result<T, HttpError>
+    where
+        F: Future<Output = Result<T, HttpError>>,
+    {
+        match deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline.into(), future)
+                .await
+                .map_err(|_| HttpError::Timeout(String::from("operation timed out")))?,
+            None => future.await,
+        }
+    }
+
+    /// Response of reqwest's one. Only bodies that are not event streams, which stay open
+    /// as long as the server wants, must be read before `deadline`
+    async fn convert_to_app_response(
+        response: reqwest::Response,
+        deadline: Option<Instant>,
+        on_event: &EventListener,
+    ) -> Result<Response, HttpError> {
         let status: i32 = response.status().as_u16().into();
+        let http_version = ReqwestClientRepository::format_version(response.version());
         let headers: HashMap<String, String> = response
             .headers()
             .iter()
             .map(|(key, value)| {
                 (
                     key.as_str().to_string(),
-                    value.to_str().unwrap().to_string(),
+                    String::from_utf8_lossy(value.as_bytes()).to_string(),
                 )
             })
             .collect();
 
-        let body = response.text().await.map_err(|e| e.to_string())?;
+        let content_type_header = headers.get(header::CONTENT_TYPE.as_str()).map(|v| v.as_str());
+        let is_event_stream = content_type_header
+            .map(|v| v.starts_with(EVENT_STREAM_CONTENT_TYPE))
+            .unwrap_or(false);
+
+        let (raw_body, events) = if is_event_stream {
+            ReqwestClientRepository::read_event_stream(response, on_event).await?
+        } else {
+            let raw_body = ReqwestClientRepository::until(deadline, async {
+                response
+                    .bytes()
+                    .await
+                    .map_err(|e| HttpError::Body(e.to_string()))
+            })
+            .await?;
+            (raw_body.to_vec(), vec![])
+        };
+
+        // reqwest doesn't decode the body, so the size it had is known
+        let content_encoding = headers.get(header::CONTENT_ENCODING.as_str());
+        let (raw_body, compression) = match content_encoding {
+            Some(encoding) if !is_event_stream => {
+                match compression::decode(encoding, &raw_body).map_err(HttpError::Body)? {
+                    Some(decoded) => {
+                        let compression = ResponseCompression {
+                            encoding: encoding.clone(),
+                            compressed_size: raw_body.len(),
+                            decoded_size: decoded.len(),
+                        };
+                        (decoded, Some(compression))
+                    }
+                    None => (raw_body, None),
+                }
+            }
+            _ => (raw_body, None),
+        };
+
+        let content_type = body::detect_content_type(content_type_header, &raw_body);
+        let body = body::body_as_text(content_type.as_deref(), &raw_body);
 
         Ok(Response {
+            stage: ResponseStage::Finished,
             status,
+            http_version: Some(http_version),
             body,
-            response_time: 1,
+            response_time: ResponseTime::default(),
             headers,
+            raw_body,
+            content_type,
+            compression,
+            redirects: vec![],
+            events,
+            graphql: None,
+            grpc_status: None,
+            trailers: HashMap::new(),
         })
     }
+
+    // The body of a stream only ends when the server closes it, so each event is given
+    // to 'on_event' as soon as it's complete
+    async fn read_event_stream(
+        mut response: reqwest::Response,
+        on_event: &EventListener,
+    ) -> Result<(Vec<u8>, Vec<ServerSentEvent>), HttpError> {
+        let mut parser = SseParser::default();
+        let mut raw_body = vec![];
+        let mut events = vec![];
+
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| HttpError::Body(e.to_string()))?
+        {
+            raw_body.extend_from_slice(&chunk);
+
+            for event in parser.feed(&chunk) {
+                on_event(event.clone());
+                events.push(event);
+            }
+        }
+
+        Ok((raw_body, events))
+    }
+
+    /// Socket and URL of `unix:///path/to.sock:/path?query`. The URL is given as
+    /// `http://localhost/path?query`, so the request is built as any other one
+    fn split_unix_url(url: &str) -> Option<(String, String)> {
+        let rest = url.strip_prefix(UNIX_SCHEME)?;
+        let (socket, path) = rest.split_once(':').unwrap_or((rest, "/"));
+
+        if socket.is_empty() {
+            return None;
+        }
+
+        let separator = if path.starts_with('/') { "" } else { "/" };
+        Some((
+            socket.to_string(),
+            format!("http://localhost{}{}", separator, path),
+        ))
+    }
+
+    fn format_version(version: Version) -> String {
+        match version {
+            Version::HTTP_09 => String::from("HTTP/0.9"),
+            Version::HTTP_10 => String::from("HTTP/1.0"),
+            Version::HTTP_11 => String::from("HTTP/1.1"),
+            Version::HTTP_2 => String::from("HTTP/2"),
+            Version::HTTP_3 => String::from("HTTP/3"),
+            _ => format!("{:?}", version),
+        }
+    }
+
+    // Events are parsed as they come, so streams are asked without compression
+    fn should_ask_compression(headers: &HeaderMap) -> bool {
+        let asks_event_stream = headers.get_all(header::ACCEPT).iter().any(|value| {
+            String::from_utf8_lossy(value.as_bytes()).contains(EVENT_STREAM_CONTENT_TYPE)
+        });
+
+        !asks_event_stream && !headers.contains_key(header::ACCEPT_ENCODING)
+    }
+
+    fn get_redirect_location(response: &Response, url: &Url) -> Option<Url> {
+        if !(300..400).contains(&response.status) {
+            return None;
+        }
+
+        let location = response.headers.get(header::LOCATION.as_str())?;
+        url.join(location).ok()
+    }
+
+    /// Method of the request after a redirect, and if its body is still sent.
+    /// As browsers do, 303 and a POST redirected by 301 or 302 become GET
+    fn get_redirect_method(status: i32, method: &Method) -> (Method, bool) {
+        match status {
+            303 if method != Method::HEAD => (Method::GET, false),
+            301 | 302 if method == Method::POST => (Method::GET, false),
+            _ => (method.clone(), true),
+        }
+    }
+
+    fn is_same_origin(a: &Url, b: &Url) -> bool {
+        a.scheme() == b.scheme()
+            && a.host_str() == b.host_str()
+            && a.port_or_known_default() == b.port_or_known_default()
+    }
 }
 
 #[async_trait]
 impl HttpClientRepository for ReqwestClientRepository {
-    async fn call_get(
+    async fn execute(
         &self,
-        url: String,
-        headers: HashMap<String, String>,
-    ) -> Result<Response, String> {
-        let client = Client::new();
-        let response = client.get(url).send().await.map_err(|e| e.to_string())?;
-        ReqwestClientRepository::convert_to_app_response(response).await
+        request: PreparedRequest,
+        on_event: &EventListener,
+    ) -> Result<Response, HttpError> {
+        let PreparedRequest {
+            method,
+            url,
+            headers,
+            body,
+            raw_body,
+            options,
+        } = request;
+
+        let mut method = Method::from_bytes(method.to_string().as_bytes())
+            .map_err(|e| HttpError::InvalidMethod(e.to_string()))?;
+        // Requests to a Unix socket keep an http:// URL, for the Host and redirects
+        let (socket, url) = match ReqwestClientRepository::split_unix_url(&url) {
+            Some((socket, url)) => (Some(socket), url),
+            None => (options.unix_socket.clone(), url),
+        };
+        let mut url = Url::parse(&url).map_err(|e| HttpError::InvalidUrl(e.to_string()))?;
+        let mut headers = ReqwestClientRepository::create_header_map(headers)?;
+        if ReqwestClientRepository::should_ask_compression(&headers) {
+            headers.insert(
+                header::ACCEPT_ENCODING,
+                HeaderValue::from_static(compression::ACCEPT_ENCODING),
+            );
+        }
+        let mut body = Some(raw_body.unwrap_or_else(|| body.into_bytes()));
+
+        let http_version = options
+            .get_http_version()
+            .map_err(HttpError::InvalidHttpVersion)?;
+        if http_version == HttpVersion::H2 && url.scheme() != "https" {
+            return Err(HttpError::InvalidHttpVersion(String::from(
+                "h2 is agreed by ALPN only in https, use \"http2\" for prior knowledge",
+            )));
+        }
+
+        let client = self.get_client(&ClientOptions::from(&options))?;
+        let mut redirects = vec![];
+
+        // The timeout is of all the hops, not of each one
+        let deadline = options
+            .get_timeout()
+            .map(|timeout| Instant::now() + timeout);
+
+        loop {
+            let mut request = client
+                .request(method.clone(), url.clone())
+                .headers(headers.clone());
+
+            if let Some(body) = &body {
+                request = request.body(body.clone());
+            }
+
+            let mut response = self
+                .send(request, socket.as_deref(), deadline, on_event)
+                .await?;
+
+            let version = response.http_version.as_deref().unwrap_or_default();
+            if http_version == HttpVersion::H2 && version != "HTTP/2" {
+                return Err(HttpError::InvalidHttpVersion(format!(
+                    "{} answered with {}, it didn't accept h2 by ALPN",
+                    url.host_str().unwrap_or_default(),
+                    version
+                )));
+            }
+
+            let next_url = match ReqwestClientRepository::get_redirect_location(&response, &url) {
+                Some(next_url) if options.should_follow_redirect(redirects.len()) => next_url,
+                _ => {
+                    response.redirects = redirects;
+                    return Ok(response);
+                }
+            };
+
+            redirects.push(RedirectHop {
+                method: method.to_string(),
+                url: url.to_string(),
+                status: response.status,
+                location: next_url.to_string(),
+                time: response.response_time.total,
+            });
+
+            let (next_method, keep_body) =
+                ReqwestClientRepository::get_redirect_method(response.status, &method);
+            if !keep_body {
+                body = None;
+                headers.remove(header::CONTENT_TYPE);
+                headers.remove(header::CONTENT_LENGTH);
+            }
+
+            // Credentials are not sent to other servers
+            if !ReqwestClientRepository::is_same_origin(&url, &next_url) {
+                headers.remove(header::AUTHORIZATION);
+                headers.remove(header::PROXY_AUTHORIZATION);
+                headers.remove(header::COOKIE);
+            }
+
+            method = next_method;
+            url = next_url;
+        }
     }
+}
 
-    async fn call_post(
-        &self,
-        url: String,
-        headers: HashMap<String, String>,
-        body: String,
-    ) -> Result<Response, String> {
-        let client = Client::new();
-        let response = client
-            .post(url)
-            .body(body)
-            .headers(ReqwestClientRepository::create_header_map(headers))
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
-        ReqwestClientRepository::convert_to_app_response(response).await
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_create_header_map() {
+        let headers = HashMap::from([
+            ("Content-Type".to_string(), "application/json".to_string()),
+            ("Authorization".to_string(), "Bearer token".to_string()),
+        ]);
+
+        let header_map = ReqwestClientRepository::create_header_map(headers).unwrap();
+
+        assert_eq!(header_map.len(), 2);
+        assert_eq!(header_map.get("authorization").unwrap(), "Bearer token");
     }
 
-    async fn call_delete(
-        &self,
-        url: String,
-        headers: HashMap<String, String>,
-        body: String,
-    ) -> Result<Response, String> {
-        let client = Client::new();
-        let response = client
-            .delete(url)
-            .body(body)
-            .headers(ReqwestClientRepository::create_header_map(headers))
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
-        ReqwestClientRepository::convert_to_app_response(response).await
+    #[test]
+    fn should_fail_with_invalid_header_name() {
+        let headers = HashMap::from([("Invalid Header".to_string(), "value".to_string())]);
+
+        let result = ReqwestClientRepository::create_header_map(headers);
+
+        assert!(matches!(result, Err(HttpError::InvalidHeader(_))));
     }
 
-    async fn call_patch(
-        &self,
-        url: String,
-        headers: HashMap<String, String>,
-        body: String,
-    ) -> Result<Response, String> {
-        let client = Client::new();
-        let response = client
-            .patch(url)
-            .body(body)
-            .headers(ReqwestClientRepository::create_header_map(headers))
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
-        ReqwestClientRepository::convert_to_app_response(response).await
+    #[test]
+    fn should_change_method_on_redirect_as_browsers() {
+        let redirect_method = ReqwestClientRepository::get_redirect_method;
+
+        assert_eq!(redirect_method(303, &Method::PUT), (Method::GET, false));
+        assert_eq!(redirect_method(303, &Method::HEAD), (Method::HEAD, true));
+        assert_eq!(redirect_method(302, &Method::POST), (Method::GET, false));
+        assert_eq!(redirect_method(302, &Method::PUT), (Method::PUT, true));
+        assert_eq!(redirect_method(307, &Method::POST), (Method::POST, true));
+        assert_eq!(redirect_method(308, &Method::POST), (Method::POST, true));
     }
 
-    async fn call_put(
-        &self,
-        url: String,
-        headers: HashMap<String, String>,
-        body: String,
-    ) -> Result<Response, String> {
-        let client = Client::new();
-        let response = client
-            .put(url)
-            .body(body)
-            .headers(ReqwestClientRepository::create_header_map(headers))
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
-        ReqwestClientRepository::convert_to_app_response(response).await
+    #[test]
+    fn should_resolve_relative_location() {
+        let url = Url::parse("http://localhost:3000/api/users").unwrap();
+        let response = Response {
+            status: 301,
+            headers: HashMap::from([("location".to_string(), "users/".to_string())]),
+            ..Response::default()
+        };
+
+        assert_eq!(
+            ReqwestClientRepository::get_redirect_location(&response, &url).unwrap(),
+            Url::parse("http://localhost:3000/api/users/").unwrap()
+        );
+
+        let response = Response {
+            status: 200,
+            ..response
+        };
+        assert_eq!(
+            ReqwestClientRepository::get_redirect_location(&response, &url),
+            None
+        );
     }
 
-    async fn call_head(
-        &self,
-        url: String,
-        headers: HashMap<String, String>,
-        body: String,
-    ) -> Result<Response, String> {
-        let client = Client::new();
-        let response = client
-            .head(url)
-            .body(body)
-            .headers(ReqwestClientRepository::create_header_map(headers))
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
-        ReqwestClientRepository::convert_to_app_response(response).await
+    #[test]
+    fn should_split_socket_and_path_of_url() {
+        assert_eq!(
+            ReqwestClientRepository::split_unix_url(
+                "unix:///var/run/docker.sock:/v1.41/containers/json?all=1"
+            ),
+            Some((
+                String::from("/var/run/docker.sock"),
+                String::from("http://localhost/v1.41/containers/json?all=1")
+            ))
+        );
+        assert_eq!(
+            ReqwestClientRepository::split_unix_url("unix:///tmp/api.sock"),
+            Some((
+                String::from("/tmp/api.sock"),
+                String::from("http://localhost/")
+            ))
+        );
+        assert_eq!(ReqwestClientRepository::split_unix_url("http://localhost/v1"), None);
+        assert_eq!(ReqwestClientRepository::split_unix_url("unix://:/v1"), None);
+    }
+
+    #[test]
+    fn should_not_ask_compression_of_event_streams() {
+        let headers = |key: &str, value: &str| {
+            ReqwestClientRepository::create_header_map(HashMap::from([(
+                key.to_string(),
+                value.to_string(),
+            )]))
+            .unwrap()
+        };
+
+        assert!(ReqwestClientRepository::should_ask_compression(&headers(
+            "Accept",
+            "application/json"
+        )));
+        assert!(!ReqwestClientRepository::should_ask_compression(&headers(
+            "Accept",
+            "text/event-stream"
+        )));
+        assert!(!ReqwestClientRepository::should_ask_compression(&headers(
+            "Accept-Encoding",
+            "identity"
+        )));
+    }
+
+    #[test]
+    fn should_format_http_version() {
+        assert_eq!(
+            ReqwestClientRepository::format_version(Version::HTTP_11),
+            "HTTP/1.1"
+        );
+        assert_eq!(ReqwestClientRepository::format_version(Version::HTTP_2), "HTTP/2");
+    }
+
+    #[tokio::test]
+    async fn should_keep_event_stream_open_after_timeout() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let _request = stream.read(&mut [0; 1024]).await.unwrap();
+            let head = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\r\n";
+            stream.write_all(head.as_bytes()).await.unwrap();
+            stream.write_all(b"data: a\n\n").await.unwrap();
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            stream.write_all(b"data: b\n\n").await.unwrap();
+        });
+
+        let repository = ReqwestClientRepository::init(
+            &WebClientConfig::default(),
+            Arc::new(CookieJar::init(vec![])),
+        )
+        .unwrap();
+        let request = PreparedRequest {
+            method: crate::base::web::request::METHODS::GET,
+            url: format!("http://{}/events", address),
+            headers: HashMap::new(),
+            body: String::new(),
+            raw_body: None,
+            options: RequestOptions {
+                timeout_ms: Some(100),
+                ..RequestOptions::default()
+            },
+        };
+
+        let response = repository.execute(request, &|_| {}).await.unwrap();
+
+        assert_eq!(response.events.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn should_time_out_redirects_all_together() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+            for hop in 1.. {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let _request = stream.read(&mut [0; 1024]).await.unwrap();
+                tokio::time::sleep(Duration::from_millis(150)).await;
+                let head = format!(
+                    "HTTP/1.1 302 Found\r\nLocation: /{}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    hop
+                );
+                stream.write_all(head.as_bytes()).await.unwrap();
+            }
+        });
+
+        let repository = ReqwestClientRepository::init(
+            &WebClientConfig::default(),
+            Arc::new(CookieJar::init(vec![])),
+        )
+        .unwrap();
+        let request = PreparedRequest {
+            method: crate::base::web::request::METHODS::GET,
+            url: format!("http://{}/0", address),
+            headers: HashMap::new(),
+            body: String::new(),
+            raw_body: None,
+            options: RequestOptions {
+                timeout_ms: Some(400),
+                ..RequestOptions::default()
+            },
+        };
+
+        let result = repository.execute(request, &|_| {}).await;
+
+        assert!(matches!(result, Err(HttpError::Timeout(_))));
     }
 }
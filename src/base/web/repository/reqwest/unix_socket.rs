@@ -0,0 +1,100 @@
+use hyper::client::conn;
+use reqwest::header::{HeaderValue, HOST};
+use reqwest::{Request, Response};
+use std::io;
+use tokio::net::UnixStream;
+
+use crate::base::web::repository::HttpError;
+
+/// Sends `request` through the Unix socket at `socket` path, instead of the network.
+/// reqwest only connects by TCP, so it's sent by hyper in a connection of its own
+pub async fn send(socket: &str, request: Request) -> Result<Response, HttpError> {
+    let stream = UnixStream::connect(socket).await.map_err(|e| {
+        let error = io::Error::new(e.kind(), format!("{}: {}", socket, e));
+        HttpError::from_connection_error(&error)
+    })?;
+    let (mut sender, connection) = conn::handshake(stream)
+        .await
+        .map_err(|e| HttpError::from_connection_error(&e))?;
+
+    // The connection is driven by its own task, until the body is read
+    tokio::spawn(connection);
+
+    let url = request.url();
+    let target = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+    let host = HeaderValue::from_str(url.host_str().unwrap_or("localhost"))
+        .map_err(|e| HttpError::InvalidUrl(e.to_string()))?;
+    let body = request
+        .body()
+        .and_then(|body| body.as_bytes())
+        .map(|bytes| bytes.to_vec())
+        .unwrap_or_default();
+
+    let mut hyper_request = hyper::Request::new(hyper::Body::from(body));
+    *hyper_request.method_mut() = request.method().clone();
+    *hyper_request.uri_mut() = target
+        .parse()
+        .map_err(|e: hyper::http::uri::InvalidUri| HttpError::InvalidUrl(e.to_string()))?;
+    *hyper_request.headers_mut() = request.headers().clone();
+    hyper_request.headers_mut().entry(HOST).or_insert(host);
+
+    let response = sender
+        .send_request(hyper_request)
+        .await
+        .map_err(|e| HttpError::from_connection_error(&e))?;
+
+    Ok(Response::from(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::{Method, Url};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::UnixListener;
+
+    #[tokio::test]
+    async fn should_fail_when_nothing_listens_in_socket() {
+        let socket = std::env::temp_dir().join(format!("treq-{}.sock", uuid::Uuid::new_v4()));
+        drop(UnixListener::bind(&socket).unwrap());
+
+        let url = Url::parse("http://localhost/").unwrap();
+        let result = send(socket.to_str().unwrap(), Request::new(Method::GET, url)).await;
+
+        assert!(matches!(result, Err(HttpError::ConnectionRefused(_))));
+        std::fs::remove_file(socket).ok();
+    }
+
+    #[tokio::test]
+    async fn should_send_request_through_socket() {
+        let socket = std::env::temp_dir().join(format!("treq-{}.sock", uuid::Uuid::new_v4()));
+        let listener = UnixListener::bind(&socket).unwrap();
+
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buffer = vec![0; 1024];
+            let read = stream.read(&mut buffer).await.unwrap();
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n[]")
+                .await
+                .unwrap();
+            String::from_utf8_lossy(&buffer[..read]).to_string()
+        });
+
+        let url = Url::parse("http://localhost/containers/json?all=1").unwrap();
+        let request = Request::new(Method::GET, url);
+        let response = send(socket.to_str().unwrap(), request).await.unwrap();
+
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.text().await.unwrap(), "[]");
+
+        let received = server.await.unwrap();
+        assert!(received.starts_with("GET /containers/json?all=1 HTTP/1.1\r\n"));
+        assert!(received.to_lowercase().contains("host: localhost"));
+
+        std::fs::remove_file(socket).ok();
+    }
+}
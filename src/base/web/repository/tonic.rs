@@ -0,0 +1,525 @@
+use async_trait::async_trait;
+use prost::Message;
+use prost_reflect::DescriptorPool;
+use prost_types::FileDescriptorProto;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Instant;
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::metadata::{MetadataKey, MetadataMap, MetadataValue};
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity};
+use tonic_reflection::pb::server_reflection_client::ServerReflectionClient;
+use tonic_reflection::pb::server_reflection_request::MessageRequest;
+use tonic_reflection::pb::server_reflection_response::MessageResponse;
+use tonic_reflection::pb::ServerReflectionRequest;
+
+use crate::base::web::grpc::{self, DynamicCodec, GrpcStatus, GrpcTarget};
+use crate::base::web::options::RequestOptions;
+use crate::base::web::response::{Response, ResponseStage, ResponseTime};
+
+use super::{GrpcClientRepository, HttpError, PreparedRequest};
+
+// Service of the reflection itself, it's not listed with the others
+static REFLECTION_SERVICE: &str = "grpc.reflection.v1alpha.ServerReflection";
+
+// Headers set by gRPC itself, the ones of Request would break the call
+static RESERVED_HEADERS: [&str; 3] = ["content-type", "te", "user-agent"];
+
+#[derive(Default)]
+pub struct TonicClientRepository;
+
+impl TonicClientRepository {
+    async fn connect(target: &GrpcTarget, options: &RequestOptions) -> Result<Channel, HttpError> {
+        let mut endpoint = Endpoint::from_shared(target.endpoint.clone())
+            .map_err(|e| HttpError::InvalidUrl(e.to_string()))?;
+
+        if let Some(timeout) = options.get_connect_timeout() {
+            endpoint = endpoint.connect_timeout(timeout);
+        }
+        if let Some(timeout) = options.get_timeout() {
+            endpoint = endpoint.timeout(timeout);
+        }
+        if target.is_tls {
+            endpoint = endpoint
+                .tls_config(Self::create_tls_config(options)?)
+                .map_err(|e| HttpError::InvalidTls(e.to_string()))?;
+        }
+
+        endpoint
+            .connect()
+            .await
+            .map_err(|e| HttpError::from_connection_error(&e))
+    }
+
+    // Only PEM files, the other TLS options are of the HTTP client
+    fn create_tls_config(options: &RequestOptions) -> Result<ClientTlsConfig, HttpError> {
+        let read = |path: &String| {
+            std::fs::read(path).map_err(|e| HttpError::InvalidTls(format!("{}: {}", path, e)))
+        };
+
+        let mut config = ClientTlsConfig::new();
+        if let Some(ca_cert) = &options.ca_cert {
+            config = config.ca_certificate(Certificate::from_pem(read(ca_cert)?));
+        }
+        if let Some(client_cert) = &options.client_cert {
+            let cert = read(client_cert)?;
+            let key = match &options.client_key {
+                Some(client_key) => read(client_key)?,
+                None => cert.clone(),
+            };
+            config = config.identity(Identity::from_pem(cert, key));
+        }
+
+        Ok(config)
+    }
+
+    fn create_metadata(headers: &HashMap<String, String>) -> Result<MetadataMap, HttpError> {
+        let mut metadata = MetadataMap::new();
+
+        for (key, value) in headers.iter() {
+            if RESERVED_HEADERS.contains(&key.to_lowercase().as_str()) {
+                continue;
+            }
+
+            let name = MetadataKey::from_str(&key.to_lowercase())
+                .map_err(|e| HttpError::InvalidHeader(format!("{}: {}", key, e)))?;
+            let value = MetadataValue::from_str(value)
+                .map_err(|e| HttpError::InvalidHeader(format!("{}: {}", key, e)))?;
+            metadata.insert(name, value);
+        }
+
+        Ok(metadata)
+    }
+
+    fn metadata_as_map(metadata: &MetadataMap) -> HashMap<String, String> {
+        metadata
+            .clone()
+            .into_headers()
+            .iter()
+            .map(|(key, value)| {
+                (
+                    key.to_string(),
+                    String::from_utf8_lossy(value.as_bytes()).to_string(),
+                )
+            })
+            .collect()
+    }
+
+    async fn ask_reflection(
+        client: &mut ServerReflectionClient<Channel>,
+        message: MessageRequest,
+    ) -> Result<MessageResponse, HttpError> {
+        let request = ServerReflectionRequest {
+            host: String::new(),
+            message_request: Some(message),
+        };
+
+        let to_error = |e: tonic::Status| {
+            HttpError::InvalidDescriptor(format!(
+                "server reflection failed ({}), set 'proto_files' in the options",
+                e.message()
+            ))
+        };
+
+        let mut responses = client
+            .server_reflection_info(futures_util::stream::iter(vec![request]))
+            .await
+            .map_err(to_error)?
+            .into_inner();
+
+        let response = responses
+            .message()
+            .await
+            .map_err(to_error)?
+            .and_then(|response| response.message_response)
+            .ok_or_else(|| {
+                HttpError::InvalidDescriptor(String::from("empty answer of reflection"))
+            })?;
+
+        match response {
+            MessageResponse::ErrorResponse(e) => Err(HttpError::InvalidDescriptor(e.error_message)),
+            response => Ok(response),
+        }
+    }
+
+    fn decode_files(response: MessageResponse) -> Result<Vec<FileDescriptorProto>, HttpError> {
+        match response {
+            MessageResponse::FileDescriptorResponse(files) => files
+                .file_descriptor_proto
+                .iter()
+                .map(|bytes| FileDescriptorProto::decode(bytes.as_slice()))
+                .collect::<Result<_, _>>()
+                .map_err(|e| HttpError::InvalidDescriptor(e.to_string())),
+            _ => Err(HttpError::InvalidDescriptor(String::from(
+                "reflection did not answer with files",
+            ))),
+        }
+    }
+
+    // Files of every service, and the ones imported by them
+    async fn fetch_by_reflection(channel: Channel) -> Result<DescriptorPool, HttpError> {
+        let mut client = ServerReflectionClient::new(channel);
+
+        let services =
+            match Self::ask_reflection(&mut client, MessageRequest::ListServices(String::new()))
+                .await?
+            {
+                MessageResponse::ListServicesResponse(list) => list.service,
+                _ => vec![],
+            };
+
+        let mut files: Vec<FileDescriptorProto> = vec![];
+        for service in services.iter().filter(|s| s.name != REFLECTION_SERVICE) {
+            let response = Self::ask_reflection(
+                &mut client,
+                MessageRequest::FileContainingSymbol(service.name.clone()),
+            )
+            .await?;
+            grpc::add_files(&mut files, Self::decode_files(response)?);
+        }
+
+        loop {
+            let missing = grpc::get_missing_dependencies(&files);
+            if missing.is_empty() {
+                break;
+            }
+            for name in missing {
+                let response =
+                    Self::ask_reflection(&mut client, MessageRequest::FileByFilename(name)).await?;
+                grpc::add_files(&mut files, Self::decode_files(response)?);
+            }
+        }
+
+        grpc::pool_from_files(files).map_err(HttpError::InvalidDescriptor)
+    }
+
+    async fn get_descriptors(
+        request: &PreparedRequest,
+        channel: Channel,
+    ) -> Result<DescriptorPool, HttpError> {
+        match &request.options.proto_files {
+            Some(paths) if !paths.is_empty() => {
+                grpc::load_descriptor_files(paths).map_err(HttpError::InvalidDescriptor)
+            }
+            _ => Self::fetch_by_reflection(channel).await,
+        }
+    }
+}
+
+#[async_trait]
+impl GrpcClientRepository for TonicClientRepository {
+    async fn fetch_descriptors(
+        &self,
+        request: PreparedRequest,
+    ) -> Result<DescriptorPool, HttpError> {
+        let target = GrpcTarget::parse(&request.url).map_err(HttpError::InvalidUrl)?;
+        let channel = Self::connect(&target, &request.options).await?;
+        Self::get_descriptors(&request, channel).await
+    }
+
+    async fn execute(&self, request: PreparedRequest) -> Result<Response, HttpError> {
+        let target = GrpcTarget::parse(&request.url).map_err(HttpError::InvalidUrl)?;
+        let channel = Self::connect(&target, &request.options).await?;
+        let pool = Self::get_descriptors(&request, channel.clone()).await?;
+
+        let method = target.find_method(&pool).map_err(HttpError::InvalidUrl)?;
+        let messages =
+            grpc::messages_from_json(&request.body, &method).map_err(HttpError::InvalidMessage)?;
+        let path = format!("/{}/{}", method.parent_service().full_name(), method.name());
+        let path =
+            PathAndQuery::from_str(&path).map_err(|e| HttpError::InvalidUrl(e.to_string()))?;
+
+        let mut call = tonic::Request::new(futures_util::stream::iter(messages));
+        *call.metadata_mut() = Self::create_metadata(&request.headers)?;
+
+        let mut client = tonic::client::Grpc::new(channel);
+        client
+            .ready()
+            .await
+            .map_err(|e| HttpError::from_connection_error(&e))?;
+
+        // Every kind of method is a stream of messages in the wire
+        let started = Instant::now();
+        let codec = DynamicCodec::init(method.output());
+        let (headers, received, trailers, status) = match client.streaming(call, path, codec).await
+        {
+            Ok(response) => {
+                let headers = Self::metadata_as_map(response.metadata());
+                let mut stream = response.into_inner();
+                let mut received = vec![];
+
+                let status = loop {
+                    match stream.message().await {
+                        Ok(Some(message)) => received.push(message),
+                        Ok(None) => break Ok(()),
+                        Err(status) => break Err(status),
+                    }
+                };
+
+                match status {
+                    Ok(()) => {
+                        let trailers = match stream.trailers().await {
+                            Ok(Some(trailers)) => Self::metadata_as_map(&trailers),
+                            _ => HashMap::new(),
+                        };
+                        (headers, received, trailers, GrpcStatus::ok())
+                    }
+                    Err(status) => (
+                        headers,
+                        received,
+                        Self::metadata_as_map(status.metadata()),
+                        GrpcStatus::from_status(&status),
+                    ),
+                }
+            }
+            Err(status) => (
+                HashMap::new(),
+                vec![],
+                Self::metadata_as_map(status.metadata()),
+                GrpcStatus::from_status(&status),
+            ),
+        };
+        let total = started.elapsed();
+
+        let body = if status.is_ok() || !received.is_empty() {
+            grpc::messages_to_json(&received, &method)
+        } else {
+            status.message.clone()
+        };
+
+        Ok(Response {
+            stage: ResponseStage::Finished,
+            status: 200,
+            http_version: Some(String::from("HTTP/2")),
+            response_time: ResponseTime {
+                total,
+                ttfb: total,
+                ..ResponseTime::default()
+            },
+            headers,
+            raw_body: body.as_bytes().to_vec(),
+            body,
+            content_type: Some(String::from("application/json")),
+            trailers,
+            grpc_status: Some(status),
+            ..Response::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::web::grpc::tests::echo_descriptors;
+    use crate::base::web::request::METHODS;
+    use prost_reflect::DynamicMessage;
+    use prost_types::FileDescriptorSet;
+    use std::convert::Infallible;
+    use std::task::{Context, Poll};
+    use tonic::body::BoxBody;
+    use tonic::codegen::{http, BoxFuture, Service};
+    use tonic::server::{NamedService, UnaryService};
+    use tonic::Status;
+
+    // Answers "Say" with the same message, or an error when its text is "fail"
+    struct Say;
+
+    impl UnaryService<DynamicMessage> for Say {
+        type Response = DynamicMessage;
+        type Future = BoxFuture<tonic::Response<DynamicMessage>, Status>;
+
+        fn call(&mut self, request: tonic::Request<DynamicMessage>) -> Self::Future {
+            Box::pin(async move {
+                let message = request.into_inner();
+                let text = message.get_field_by_name("text").unwrap();
+                if text.as_str() == Some("fail") {
+                    return Err(Status::not_found("nothing to say"));
+                }
+                let mut response = tonic::Response::new(message);
+                response
+                    .metadata_mut()
+                    .insert("x-echo", MetadataValue::from_static("yes"));
+                Ok(response)
+            })
+        }
+    }
+
+    #[derive(Clone)]
+    struct EchoServer {
+        pool: DescriptorPool,
+    }
+
+    impl NamedService for EchoServer {
+        const NAME: &'static str = "test.Echo";
+    }
+
+    impl Service<http::Request<tonic::transport::Body>> for EchoServer {
+        type Response = http::Response<BoxBody>;
+        type Error = Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: http::Request<tonic::transport::Body>) -> Self::Future {
+            let message = self.pool.get_message_by_name("test.EchoRequest").unwrap();
+            Box::pin(async move {
+                let mut grpc = tonic::server::Grpc::new(DynamicCodec::init(message));
+                Ok(grpc.unary(Say, request).await)
+            })
+        }
+    }
+
+    async fn start_server() -> String {
+        let files = vec![echo_descriptors()];
+        let encoded = FileDescriptorSet {
+            file: files.clone(),
+        }
+        .encode_to_vec();
+        let reflection = tonic_reflection::server::Builder::configure()
+            .register_encoded_file_descriptor_set(&encoded)
+            .build()
+            .unwrap();
+        let echo = EchoServer {
+            pool: grpc::pool_from_files(files).unwrap(),
+        };
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(reflection)
+                .add_service(echo)
+                .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener)),
+        );
+
+        format!("grpc://{}", address)
+    }
+
+    // Server with only the reflection of 'files', their services are not called
+    async fn start_reflection_server(files: Vec<FileDescriptorProto>) -> String {
+        let encoded = FileDescriptorSet { file: files }.encode_to_vec();
+        let reflection = tonic_reflection::server::Builder::configure()
+            .register_encoded_file_descriptor_set(&encoded)
+            .build()
+            .unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(reflection)
+                .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener)),
+        );
+
+        format!("grpc://{}", address)
+    }
+
+    fn request(url: String, body: &str) -> PreparedRequest {
+        PreparedRequest {
+            method: METHODS::POST,
+            url,
+            headers: HashMap::from([("Content-Type".to_string(), "application/json".to_string())]),
+            body: body.to_string(),
+            raw_body: None,
+            options: RequestOptions::default(),
+        }
+    }
+
+    #[test]
+    fn should_skip_reserved_headers_in_metadata() {
+        let headers = HashMap::from([
+            ("Content-Type".to_string(), "application/json".to_string()),
+            ("Authorization".to_string(), "Bearer abc".to_string()),
+        ]);
+
+        let metadata = TonicClientRepository::create_metadata(&headers).unwrap();
+
+        assert_eq!(metadata.len(), 1);
+        assert_eq!(metadata.get("authorization").unwrap(), "Bearer abc");
+    }
+
+    #[tokio::test]
+    async fn should_list_methods_by_reflection() {
+        let url = start_server().await;
+
+        let pool = TonicClientRepository
+            .fetch_descriptors(request(url, ""))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            grpc::get_method_names(&pool),
+            vec!["test.Echo/Say", "test.Echo/Count"]
+        );
+    }
+
+    #[tokio::test]
+    async fn should_list_methods_of_services_sharing_an_import() {
+        let mut shared = echo_descriptors();
+        shared.name = Some(String::from("shared.proto"));
+        shared.service = vec![];
+        let service = |file: &str, name: &str| {
+            let mut file = FileDescriptorProto {
+                name: Some(file.to_string()),
+                dependency: vec![String::from("shared.proto")],
+                ..echo_descriptors()
+            };
+            file.message_type = vec![];
+            file.service[0].name = Some(name.to_string());
+            file.service[0].method.truncate(1);
+            file
+        };
+        let files = vec![
+            shared,
+            service("a.proto", "First"),
+            service("b.proto", "Second"),
+        ];
+        let url = start_reflection_server(files).await;
+
+        let pool = TonicClientRepository
+            .fetch_descriptors(request(url, ""))
+            .await
+            .unwrap();
+
+        let mut names = grpc::get_method_names(&pool);
+        names.sort();
+        assert_eq!(names, vec!["test.First/Say", "test.Second/Say"]);
+    }
+
+    #[tokio::test]
+    async fn should_call_method_with_json_message() {
+        let url = start_server().await;
+
+        let response = TonicClientRepository
+            .execute(request(
+                format!("{}/test.Echo/Say", url),
+                r#"{ "text": "hi" }"#,
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(response.grpc_status, Some(GrpcStatus::ok()));
+        assert_eq!(response.body, "{\n  \"text\": \"hi\"\n}");
+        assert_eq!(response.headers.get("x-echo").unwrap(), "yes");
+    }
+
+    #[tokio::test]
+    async fn should_show_status_of_failed_call() {
+        let url = start_server().await;
+
+        let response = TonicClientRepository
+            .execute(request(
+                format!("{}/test.Echo/Say", url),
+                r#"{ "text": "fail" }"#,
+            ))
+            .await
+            .unwrap();
+
+        let status = response.grpc_status.unwrap();
+        assert_eq!(status.get_name(), "NOT_FOUND");
+        assert_eq!(status.message, "nothing to say");
+        assert_eq!(response.body, "nothing to say");
+    }
+}
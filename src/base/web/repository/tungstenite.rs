@@ -0,0 +1,225 @@
+use futures_util::{SinkExt, StreamExt};
+use std::str::FromStr;
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::header::{HeaderName, HeaderValue};
+use tokio_tungstenite::tungstenite::http::Request;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::Connector;
+
+use crate::base::web::websocket::{
+    FrameDirection, FrameKind, WebSocketConnection, WebSocketEvent, WebSocketFrame,
+    WebSocketListener, WebSocketMessage,
+};
+
+use super::tls::TlsOptions;
+use super::{HttpError, PreparedRequest, SocketClientRepository};
+
+#[derive(Default)]
+pub struct TungsteniteClientRepository;
+
+impl TungsteniteClientRepository {
+    fn create_request(request: &PreparedRequest) -> Result<Request<()>, HttpError> {
+        let mut ws_request = request
+            .url
+            .as_str()
+            .into_client_request()
+            .map_err(|e| HttpError::InvalidUrl(e.to_string()))?;
+
+        for (key, value) in request.headers.iter() {
+            let name = HeaderName::from_str(key)
+                .map_err(|e| HttpError::InvalidHeader(format!("{}: {}", key, e)))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|e| HttpError::InvalidHeader(format!("{}: {}", key, e)))?;
+            ws_request.headers_mut().insert(name, value);
+        }
+
+        Ok(ws_request)
+    }
+
+    fn to_frame(direction: FrameDirection, message: &Message) -> Option<WebSocketFrame> {
+        let (kind, data) = match message {
+            Message::Text(text) => (FrameKind::Text, text.as_bytes().to_vec()),
+            Message::Binary(data) => (FrameKind::Binary, data.clone()),
+            Message::Ping(data) => (FrameKind::Ping, data.clone()),
+            Message::Pong(data) => (FrameKind::Pong, data.clone()),
+            Message::Close(close) => {
+                let reason = match close {
+                    Some(close) => format!("{} {}", u16::from(close.code), close.reason),
+                    None => String::new(),
+                };
+                (FrameKind::Close, reason.trim_end().as_bytes().to_vec())
+            }
+            Message::Frame(_) => return None,
+        };
+
+        Some(WebSocketFrame::init(direction, kind, data))
+    }
+
+    async fn run(
+        request: PreparedRequest,
+        mut messages: UnboundedReceiver<WebSocketMessage>,
+        on_event: &WebSocketListener,
+    ) -> Result<(), String> {
+        let ws_request = Self::create_request(&request).map_err(|e| e.to_string())?;
+
+        // Used only by wss://, with the same certificates of the HTTP requests
+        let connector = TlsOptions::from(&request.options)
+            .connector()
+            .map_err(|e| e.to_string())?;
+        let connecting = tokio_tungstenite::connect_async_tls_with_config(
+            ws_request,
+            None,
+            Some(Connector::NativeTls(connector)),
+        );
+        let connected = match request.options.get_connect_timeout() {
+            Some(timeout) => tokio::time::timeout(timeout, connecting)
+                .await
+                .map_err(|_| String::from("Timeout connecting"))?,
+            None => connecting.await,
+        };
+        let (stream, _) = connected.map_err(|e| e.to_string())?;
+
+        on_event(WebSocketEvent::Opened);
+        let (mut write, mut read) = stream.split();
+
+        // After a Close is sent, it only waits for the answer of server
+        let mut is_closing = false;
+
+        loop {
+            tokio::select! {
+                message = messages.recv(), if !is_closing => {
+                    let message = match message {
+                        Some(WebSocketMessage::Text(text)) => Message::Text(text),
+                        Some(WebSocketMessage::Binary(data)) => Message::Binary(data),
+                        // All handles dropped is the same of closing it
+                        Some(WebSocketMessage::Close) | None => {
+                            is_closing = true;
+                            Message::Close(None)
+                        }
+                    };
+
+                    let frame = Self::to_frame(FrameDirection::Sent, &message);
+                    write.send(message).await.map_err(|e| e.to_string())?;
+                    if let Some(frame) = frame {
+                        on_event(WebSocketEvent::Frame(frame));
+                    }
+                }
+
+                // The answer of Close and Pings are sent by tungstenite itself
+                received = read.next() => match received {
+                    Some(Ok(message)) => {
+                        if let Some(frame) = Self::to_frame(FrameDirection::Received, &message) {
+                            on_event(WebSocketEvent::Frame(frame));
+                        }
+                    }
+                    Some(Err(e)) => return Err(e.to_string()),
+                    None => return Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl SocketClientRepository for TungsteniteClientRepository {
+    fn connect(
+        &self,
+        request: PreparedRequest,
+        on_event: Box<WebSocketListener>,
+    ) -> WebSocketConnection {
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        tokio::task::spawn(async move {
+            let result = Self::run(request, receiver, on_event.as_ref()).await;
+            on_event(WebSocketEvent::Closed(result.err()));
+        });
+
+        WebSocketConnection::init(sender)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+    use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+
+    #[test]
+    fn should_create_request_with_headers() {
+        let request = PreparedRequest {
+            method: crate::base::web::request::METHODS::GET,
+            url: String::from("wss://example.com/socket"),
+            headers: HashMap::from([("Authorization".to_string(), "Bearer abc".to_string())]),
+            body: String::new(),
+            raw_body: None,
+            options: Default::default(),
+        };
+
+        let ws_request = TungsteniteClientRepository::create_request(&request).unwrap();
+
+        assert_eq!(ws_request.headers().get("authorization").unwrap(), "Bearer abc");
+        assert!(ws_request.headers().contains_key("sec-websocket-key"));
+    }
+
+    #[test]
+    fn should_show_reason_of_close() {
+        let close = Message::Close(Some(CloseFrame {
+            code: CloseCode::Normal,
+            reason: "bye".into(),
+        }));
+
+        let frame = TungsteniteClientRepository::to_frame(FrameDirection::Received, &close);
+
+        assert_eq!(frame.unwrap().get_data_as_text(), "1000 bye");
+    }
+
+    #[tokio::test]
+    async fn should_send_and_receive_frames() {
+        // Server that answers each message with it in uppercase
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+            while let Some(Ok(Message::Text(text))) = socket.next().await {
+                socket.send(Message::Text(text.to_uppercase())).await.unwrap();
+            }
+        });
+
+        let (events_sender, mut events) = mpsc::unbounded_channel();
+        let request = PreparedRequest {
+            method: crate::base::web::request::METHODS::GET,
+            url: format!("ws://{}", address),
+            headers: HashMap::new(),
+            body: String::new(),
+            raw_body: None,
+            options: Default::default(),
+        };
+
+        let connection = TungsteniteClientRepository.connect(
+            request,
+            Box::new(move |event| events_sender.send(event).unwrap_or(())),
+        );
+        connection
+            .send(WebSocketMessage::Text("hello".to_string()))
+            .unwrap();
+
+        assert_eq!(events.recv().await, Some(WebSocketEvent::Opened));
+
+        let mut frames = vec![];
+        while frames.len() < 2 {
+            if let Some(WebSocketEvent::Frame(frame)) = events.recv().await {
+                frames.push((frame.direction, frame.get_data_as_text()));
+            }
+        }
+
+        assert_eq!(
+            frames,
+            vec![
+                (FrameDirection::Sent, "hello".to_string()),
+                (FrameDirection::Received, "HELLO".to_string()),
+            ]
+        );
+    }
+}
@@ -1,14 +1,29 @@
 use serde::{self, Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+use super::auth::Auth;
+use super::form::{FormField, FORM_CONTENT_TYPE};
+use super::graphql::parser::parse_document;
+use super::graphql::GraphQLRequest;
+use super::multipart::MultipartPart;
+use super::options::RequestOptions;
+use super::query::{self, DisabledParam, QueryParam};
+use super::signing::Signer;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum METHODS {
+    CONNECT,
     DELETE,
     GET,
     HEAD,
+    OPTIONS,
     PATCH,
     POST,
     PUT,
+    TRACE,
+
+    // Any other verb, like PURGE or PROPFIND
+    Custom(String),
 }
 impl ToString for METHODS {
     fn to_string(&self) -> String {
@@ -19,9 +34,45 @@ impl ToString for METHODS {
             Self::PATCH => "PATCH".to_string(),
             Self::PUT => "PUT".to_string(),
             Self::DELETE => "DELETE".to_string(),
+            Self::OPTIONS => "OPTIONS".to_string(),
+            Self::TRACE => "TRACE".to_string(),
+            Self::CONNECT => "CONNECT".to_string(),
+            Self::Custom(method) => method.clone(),
         }
     }
 }
+impl METHODS {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        let name = name.trim();
+
+        let method = match name.to_uppercase().as_str() {
+            "GET" => Self::GET,
+            "POST" => Self::POST,
+            "HEAD" => Self::HEAD,
+            "PATCH" => Self::PATCH,
+            "PUT" => Self::PUT,
+            "DELETE" => Self::DELETE,
+            "OPTIONS" => Self::OPTIONS,
+            "TRACE" => Self::TRACE,
+            "CONNECT" => Self::CONNECT,
+            _ => {
+                // A method is a 'token' (RFC 9110), so no spaces or separators
+                let is_token = !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c));
+
+                if !is_token {
+                    return Err(format!("'{}' is not a valid method name", name));
+                }
+
+                Self::Custom(name.to_string())
+            }
+        };
+
+        Ok(method)
+    }
+}
 
 pub struct HeadersRequest;
 impl HeadersRequest {
@@ -32,6 +83,59 @@ impl HeadersRequest {
     }
 }
 
+// Kind of a tab. WebSocket ones keep a connection open, instead of doing one request,
+// GraphQL ones have the query in the body and its variables apart, and gRPC ones have
+// the method in the path of URL and its message in the body
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RequestKind {
+    #[default]
+    Http,
+    WebSocket,
+    GraphQL,
+    Grpc,
+}
+
+impl RequestKind {
+    /// Kind given by the scheme of `url`, if it has one
+    pub fn from_url(url: &str) -> Option<Self> {
+        let scheme = url.split_once("://")?.0.to_lowercase();
+        match scheme.as_str() {
+            "http" | "https" => Some(Self::Http),
+            "ws" | "wss" => Some(Self::WebSocket),
+            "grpc" | "grpcs" => Some(Self::Grpc),
+            _ => None,
+        }
+    }
+}
+
+// How the body of a HTTP request is written. Raw ones are sent as they are, the others
+// are built from their parts when sent
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BodyMode {
+    #[default]
+    Raw,
+    Multipart,
+    Form,
+}
+
+impl BodyMode {
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Raw => Self::Multipart,
+            Self::Multipart => Self::Form,
+            Self::Form => Self::Raw,
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Raw => "raw",
+            Self::Multipart => "multipart/form-data",
+            Self::Form => FORM_CONTENT_TYPE,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Request {
     pub name: String,
@@ -40,6 +144,35 @@ pub struct Request {
     pub headers: HashMap<String, String>,
     pub body: String,
 
+    #[serde(default)]
+    pub options: RequestOptions,
+
+    #[serde(default)]
+    pub kind: RequestKind,
+
+    #[serde(default)]
+    pub graphql: GraphQLRequest,
+
+    #[serde(default)]
+    pub body_mode: BodyMode,
+
+    #[serde(default)]
+    pub multipart: Vec<MultipartPart>,
+
+    #[serde(default)]
+    pub form: Vec<FormField>,
+
+    // Enabled params are in the query of URL, only the disabled ones are kept apart
+    #[serde(default)]
+    pub disabled_params: Vec<DisabledParam>,
+
+    #[serde(default)]
+    pub auth: Auth,
+
+    // Without one, the [signer] of config.toml signs it, if there is one
+    #[serde(default)]
+    pub signer: Option<Signer>,
+
     #[serde(skip)]
     pub has_changed: bool,
 }
@@ -52,20 +185,80 @@ impl Default for Request {
             url: String::new(),
             headers: HeadersRequest::default(),
             body: String::from("{}"),
+            options: RequestOptions::default(),
+            kind: RequestKind::Http,
+            graphql: GraphQLRequest::default(),
+            body_mode: BodyMode::Raw,
+            multipart: vec![],
+            form: vec![],
+            disabled_params: vec![],
+            auth: Auth::default(),
+            signer: None,
             has_changed: false,
         }
     }
 }
 
 impl Request {
+    pub fn default_websocket() -> Self {
+        Self {
+            name: String::from("New WebSocket"),
+            url: String::from("ws://"),
+            headers: HashMap::new(),
+            body: String::new(),
+            kind: RequestKind::WebSocket,
+            ..Self::default()
+        }
+    }
+
+    pub fn default_graphql() -> Self {
+        Self {
+            name: String::from("New GraphQL"),
+            method: METHODS::POST,
+            body: String::from("query {\n  __typename\n}"),
+            kind: RequestKind::GraphQL,
+            ..Self::default()
+        }
+    }
+
+    pub fn default_grpc() -> Self {
+        Self {
+            name: String::from("New gRPC"),
+            url: String::from("grpc://localhost:50051/"),
+            headers: HashMap::new(),
+            kind: RequestKind::Grpc,
+            ..Self::default()
+        }
+    }
+
     pub fn set_name(&mut self, name: String) {
         self.name = name;
     }
 
     pub fn set_url(&mut self, url: String) {
+        // GraphQL and gRPC are done by HTTP, so an http:// URL doesn't change their kind
+        match (self.kind, RequestKind::from_url(&url)) {
+            (RequestKind::GraphQL | RequestKind::Grpc, Some(RequestKind::Http)) => {}
+            (_, Some(kind)) => self.kind = kind,
+            (_, None) => {}
+        }
+        // Disabled params belong to the endpoint, another one doesn't take them
+        if query::get_endpoint(&url) != query::get_endpoint(&self.url) {
+            self.disabled_params.clear();
+        }
         self.url = url;
     }
 
+    /// Params of the query of URL, with the disabled ones where they were
+    pub fn get_params(&self) -> Vec<QueryParam> {
+        query::merge_params(query::parse_params(&self.url), &self.disabled_params)
+    }
+
+    pub fn set_params(&mut self, params: Vec<QueryParam>) {
+        self.url = query::set_params(&self.url, &params);
+        self.disabled_params = query::split_disabled(&params);
+    }
+
     pub fn set_method(&mut self, method: METHODS) {
         self.method = method;
     }
@@ -76,5 +269,174 @@ impl Request {
 
     pub fn set_body(&mut self, body: String) {
         self.body = body;
+
+        // An operation removed of the query is no longer chosen to be sent
+        if let Some(operation_name) = &self.graphql.operation_name {
+            if let Ok(document) = parse_document(&self.body) {
+                if !document.get_operation_names().contains(operation_name) {
+                    self.graphql.operation_name = None;
+                }
+            }
+        }
+    }
+
+    pub fn set_options(&mut self, options: RequestOptions) {
+        self.options = options;
+    }
+
+    pub fn set_body_mode(&mut self, body_mode: BodyMode) {
+        self.body_mode = body_mode;
+    }
+
+    pub fn set_multipart(&mut self, parts: Vec<MultipartPart>) {
+        self.multipart = parts;
+    }
+
+    pub fn set_form(&mut self, fields: Vec<FormField>) {
+        self.form = fields;
+    }
+
+    pub fn set_auth(&mut self, auth: Auth) {
+        self.auth = auth;
+    }
+
+    pub fn set_signer(&mut self, signer: Option<Signer>) {
+        self.signer = signer;
+    }
+
+    pub fn set_graphql_variables(&mut self, variables: String) {
+        self.graphql.variables = variables;
+    }
+
+    pub fn set_graphql_operation(&mut self, operation_name: Option<String>) {
+        self.graphql.operation_name = operation_name;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_get_method_from_name() {
+        assert_eq!(METHODS::from_name("GET"), Ok(METHODS::GET));
+        assert_eq!(METHODS::from_name("options"), Ok(METHODS::OPTIONS));
+        assert_eq!(METHODS::from_name(" TRACE "), Ok(METHODS::TRACE));
+        assert_eq!(
+            METHODS::from_name("PURGE"),
+            Ok(METHODS::Custom("PURGE".to_string()))
+        );
+        assert_eq!(
+            METHODS::from_name("PROPFIND").unwrap().to_string(),
+            "PROPFIND"
+        );
+    }
+
+    #[test]
+    fn should_not_accept_invalid_method_names() {
+        assert!(METHODS::from_name("").is_err());
+        assert!(METHODS::from_name("NOT VALID").is_err());
+        assert!(METHODS::from_name("GET/").is_err());
+    }
+
+    #[test]
+    fn should_save_and_load_custom_methods() {
+        let mut req = Request::default();
+        req.set_method(METHODS::Custom("PROPFIND".to_string()));
+
+        let saved = serde_json::to_string(&req).unwrap();
+        let loaded: Request = serde_json::from_str(&saved).unwrap();
+
+        assert_eq!(loaded.method, METHODS::Custom("PROPFIND".to_string()));
+    }
+
+    #[test]
+    fn should_change_kind_by_scheme_of_url() {
+        let mut request = Request::default();
+
+        request.set_url(String::from("wss://echo.example.com/socket"));
+        assert_eq!(request.kind, RequestKind::WebSocket);
+
+        request.set_url(String::from("localhost:8080"));
+        assert_eq!(request.kind, RequestKind::WebSocket);
+
+        request.set_url(String::from("HTTP://localhost:8080"));
+        assert_eq!(request.kind, RequestKind::Http);
+    }
+
+    #[test]
+    fn should_create_graphql_with_valid_query() {
+        let request = Request::default_graphql();
+
+        assert!(parse_document(&request.body).is_ok());
+    }
+
+    #[test]
+    fn should_forget_operation_removed_of_query() {
+        let mut request = Request::default_graphql();
+        request.set_body(String::from("query A { a }\nquery B { b }"));
+        request.set_graphql_operation(Some(String::from("B")));
+
+        request.set_body(String::from("query A { a }\nquery B { b c }"));
+        assert_eq!(request.graphql.operation_name.as_deref(), Some("B"));
+
+        request.set_body(String::from("query A { a }"));
+        assert_eq!(request.graphql.operation_name, None);
+    }
+
+    #[test]
+    fn should_keep_graphql_kind_with_http_urls() {
+        let mut request = Request::default_graphql();
+
+        request.set_url(String::from("https://api.example.com/graphql"));
+        assert_eq!(request.kind, RequestKind::GraphQL);
+
+        request.set_url(String::from("wss://api.example.com/graphql"));
+        assert_eq!(request.kind, RequestKind::WebSocket);
+    }
+
+    #[test]
+    fn should_keep_grpc_kind_with_http_urls() {
+        let mut request = Request::default();
+
+        request.set_url(String::from("grpcs://api.example.com/pkg.Service/Method"));
+        assert_eq!(request.kind, RequestKind::Grpc);
+
+        request.set_url(String::from("http://localhost:50051/pkg.Service/Method"));
+        assert_eq!(request.kind, RequestKind::Grpc);
+    }
+
+    #[test]
+    fn should_keep_params_in_sync_with_url() {
+        let mut request = Request::default();
+        request.set_url(String::from("https://api.com/users?page=2&sort=name"));
+
+        let mut params = request.get_params();
+        assert_eq!(params.len(), 2);
+
+        params[0].enabled = false;
+        params[1].value = String::from("created at");
+        request.set_params(params);
+        assert_eq!(request.url, "https://api.com/users?sort=created%20at");
+
+        let mut params = request.get_params();
+        assert_eq!((params[0].key.as_str(), params[0].enabled), ("page", false));
+
+        params[0].enabled = true;
+        request.set_params(params);
+        assert_eq!(
+            request.url,
+            "https://api.com/users?page=2&sort=created%20at"
+        );
+
+        request.set_params(vec![QueryParam {
+            enabled: false,
+            ..QueryParam::init("page", "2")
+        }]);
+        request.set_url(String::from("https://api.com/users?limit=10"));
+        assert_eq!(request.get_params().len(), 2);
+
+        request.set_url(String::from("https://api.com/teams?limit=10"));
+        assert_eq!(request.get_params(), vec![QueryParam::init("limit", "10")]);
     }
 }
@@ -1,11 +1,83 @@
+use super::body;
+use super::compression::ResponseCompression;
+use super::graphql::GraphQLResponse;
+use super::grpc::GrpcStatus;
+use super::sse::ServerSentEvent;
 use std::collections::HashMap;
+use std::time::Duration;
+
+// Phases of time spent in a Request, measured by the HttpClientRepository. DNS, connection
+// and TLS handshake are not exposed by the client for each request, so they are in 'ttfb'
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct ResponseTime {
+    pub total: Duration,
+    pub ttfb: Duration,
+    pub download: Duration,
+}
+
+impl ResponseTime {
+    fn format_duration(duration: &Duration) -> String {
+        let millis = duration.as_secs_f64() * 1000.0;
+        if millis < 10.0 {
+            format!("{:.1}ms", millis)
+        } else {
+            format!("{:.0}ms", millis)
+        }
+    }
+
+    pub fn to_summary(&self) -> String {
+        format!(
+            "{} (ttfb {} / download {})",
+            Self::format_duration(&self.total),
+            Self::format_duration(&self.ttfb),
+            Self::format_duration(&self.download),
+        )
+    }
+}
+
+// A redirect response received before the final one
+#[derive(Clone, Debug, PartialEq)]
+pub struct RedirectHop {
+    pub method: String,
+    pub url: String,
+    pub status: i32,
+    pub location: String,
+    pub time: Duration,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResponseStage {
+    #[default]
+    Empty,
+    Waiting,
+    Streaming,
+    Finished,
+    // No response came, the reason is kept apart as a HttpError
+    Failed,
+    Cancelled,
+}
 
 #[derive(Default, Clone, Debug)]
 pub struct Response {
+    pub stage: ResponseStage,
     pub status: i32,
-    pub response_time: i32,
+    // Protocol agreed with the server, as "HTTP/1.1" or "HTTP/2"
+    pub http_version: Option<String>,
+    pub response_time: ResponseTime,
     pub headers: HashMap<String, String>,
+    // Text to be shown, it's the hex dump of binary bodies
     pub body: String,
+    pub raw_body: Vec<u8>,
+    pub content_type: Option<String>,
+    // Encoding and sizes of a body decoded by the client
+    pub compression: Option<ResponseCompression>,
+    pub redirects: Vec<RedirectHop>,
+    pub events: Vec<ServerSentEvent>,
+    // Data and errors of the body, only for GraphQL requests
+    pub graphql: Option<GraphQLResponse>,
+    // Status of the call and trailers sent after the messages, only for gRPC requests
+    pub grpc_status: Option<GrpcStatus>,
+    pub trailers: HashMap<String, String>,
 }
 
 impl Response {
@@ -13,12 +85,45 @@ impl Response {
         *self = new_response;
     }
 
-    pub fn default_internal_error(err: String) -> Self {
+    pub fn default_waiting() -> Self {
+        Self {
+            stage: ResponseStage::Waiting,
+            ..Self::default()
+        }
+    }
+
+    pub fn default_cancelled() -> Self {
+        Self {
+            stage: ResponseStage::Cancelled,
+            body: String::from("Request cancelled"),
+            ..Self::default()
+        }
+    }
+
+    pub fn default_failed() -> Self {
         Self {
-            status: 77, // A STATUS CODE INTERNAL TO INTERNAL ERROR
-            response_time: 0,
-            headers: HashMap::new(),
-            body: err,
+            stage: ResponseStage::Failed,
+            ..Self::default()
         }
     }
+
+    pub fn is_binary(&self) -> bool {
+        body::is_binary(self.content_type.as_deref(), &self.raw_body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_summarize_response_time() {
+        let time = ResponseTime {
+            total: Duration::from_millis(153),
+            ttfb: Duration::from_millis(124),
+            download: Duration::from_micros(2_900),
+        };
+
+        assert_eq!(time.to_summary(), "153ms (ttfb 124ms / download 2.9ms)");
+    }
 }
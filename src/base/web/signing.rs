@@ -0,0 +1,432 @@
+use base64::Engine;
+use cookie::time::OffsetDateTime;
+use hmac::{Hmac, Mac};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::repository::PreparedRequest;
+
+// Characters kept as they are by AWS, all others are percent-encoded
+const UNRESERVED: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+// Signs a request just before it's sent, when its URL, headers and body are the final ones
+pub trait RequestSigner: Send + Sync {
+    fn sign(&self, request: &mut PreparedRequest, now: OffsetDateTime) -> Result<(), String>;
+}
+
+fn get_body(request: &PreparedRequest) -> &[u8] {
+    match &request.raw_body {
+        Some(raw_body) => raw_body,
+        None => request.body.as_bytes(),
+    }
+}
+
+fn hmac_sha256(key: &[u8], value: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key size");
+    mac.update(value);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn set_header(request: &mut PreparedRequest, name: &str, value: String) {
+    request
+        .headers
+        .retain(|key, _| !key.eq_ignore_ascii_case(name));
+    request.headers.insert(name.to_string(), value);
+}
+
+// AWS Signature Version 4, sent in the Authorization header
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AwsSigV4 {
+    pub access_key: String,
+    pub secret_key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_token: Option<String>,
+    pub region: String,
+    pub service: String,
+}
+
+impl AwsSigV4 {
+    // S3 encodes the path once, all other services encode it again
+    fn get_canonical_uri(&self, url: &Url) -> String {
+        let segments: Vec<String> = url
+            .path()
+            .split('/')
+            .map(|segment| match self.service.as_str() {
+                "s3" => {
+                    let decoded = percent_decode_str(segment).decode_utf8_lossy();
+                    utf8_percent_encode(&decoded, UNRESERVED).to_string()
+                }
+                _ => utf8_percent_encode(segment, UNRESERVED).to_string(),
+            })
+            .collect();
+
+        match segments.join("/") {
+            uri if uri.is_empty() => String::from("/"),
+            uri => uri,
+        }
+    }
+
+    fn get_canonical_query(url: &Url) -> String {
+        let mut pairs: Vec<(String, String)> = url
+            .query()
+            .unwrap_or_default()
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                let encode = |text: &str| {
+                    let decoded = percent_decode_str(text).decode_utf8_lossy();
+                    utf8_percent_encode(&decoded, UNRESERVED).to_string()
+                };
+                (encode(key), encode(value))
+            })
+            .collect();
+        pairs.sort();
+
+        pairs
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<String>>()
+            .join("&")
+    }
+
+    /// Headers in the form AWS signs them, and the names of them
+    fn get_canonical_headers(request: &PreparedRequest) -> (String, String) {
+        let mut headers: Vec<(String, String)> = request
+            .headers
+            .iter()
+            .map(|(key, value)| {
+                let value = value.split_whitespace().collect::<Vec<&str>>().join(" ");
+                (key.to_lowercase(), value)
+            })
+            .collect();
+        headers.sort();
+
+        let canonical = headers
+            .iter()
+            .map(|(key, value)| format!("{}:{}\n", key, value))
+            .collect();
+        let signed = headers
+            .iter()
+            .map(|(key, _)| key.as_str())
+            .collect::<Vec<&str>>()
+            .join(";");
+        (canonical, signed)
+    }
+
+    fn get_signing_key(&self, date: &str) -> Vec<u8> {
+        let key = format!("AWS4{}", self.secret_key);
+        let key = hmac_sha256(key.as_bytes(), date.as_bytes());
+        let key = hmac_sha256(&key, self.region.as_bytes());
+        let key = hmac_sha256(&key, self.service.as_bytes());
+        hmac_sha256(&key, b"aws4_request")
+    }
+}
+
+impl RequestSigner for AwsSigV4 {
+    fn sign(&self, request: &mut PreparedRequest, now: OffsetDateTime) -> Result<(), String> {
+        let url = Url::parse(&request.url).map_err(|e| e.to_string())?;
+        let date = format!("{}{:02}{:02}", now.year(), now.month() as u8, now.day());
+        let amz_date = format!(
+            "{}T{:02}{:02}{:02}Z",
+            date,
+            now.hour(),
+            now.minute(),
+            now.second()
+        );
+        let payload_hash = to_hex(&Sha256::digest(get_body(request)));
+
+        // Host is signed as the client sends it, when it's not written in the headers
+        let has_host = request
+            .headers
+            .keys()
+            .any(|key| key.eq_ignore_ascii_case("host"));
+        if !has_host {
+            let host = url.host_str().ok_or("URL without host")?;
+            let host = match url.port() {
+                Some(port) => format!("{}:{}", host, port),
+                None => host.to_string(),
+            };
+            request.headers.insert(String::from("host"), host);
+        }
+        set_header(request, "x-amz-date", amz_date.clone());
+        if self.service == "s3" {
+            set_header(request, "x-amz-content-sha256", payload_hash.clone());
+        }
+        if let Some(session_token) = &self.session_token {
+            set_header(request, "x-amz-security-token", session_token.clone());
+        }
+        request
+            .headers
+            .retain(|key, _| !key.eq_ignore_ascii_case("authorization"));
+
+        let (canonical_headers, signed_headers) = Self::get_canonical_headers(request);
+        let canonical_request = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            request.method.to_string(),
+            self.get_canonical_uri(&url),
+            Self::get_canonical_query(&url),
+            canonical_headers,
+            signed_headers,
+            payload_hash
+        );
+
+        let scope = format!("{}/{}/{}/aws4_request", date, self.region, self.service);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            to_hex(&Sha256::digest(canonical_request.as_bytes()))
+        );
+        let signature = to_hex(&hmac_sha256(
+            &self.get_signing_key(&date),
+            string_to_sign.as_bytes(),
+        ));
+
+        request.headers.insert(
+            String::from("Authorization"),
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                self.access_key, scope, signed_headers, signature
+            ),
+        );
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureEncoding {
+    #[default]
+    Hex,
+    Base64,
+}
+
+fn default_signature_header() -> String {
+    String::from("X-Signature")
+}
+
+fn default_timestamp_header() -> String {
+    String::from("X-Timestamp")
+}
+
+fn default_template() -> String {
+    String::from("{method}\n{path}\n{timestamp}\n{body}")
+}
+
+// HMAC-SHA256 of a text made of parts of the request. The 'template' may have {method},
+// {host}, {path} (with the query), {query}, {timestamp} (Unix time) and {body}
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HmacSigner {
+    pub secret: String,
+    #[serde(default = "default_signature_header")]
+    pub header: String,
+    #[serde(default)]
+    pub prefix: String,
+    #[serde(default = "default_template")]
+    pub template: String,
+    // Empty to not send it
+    #[serde(default = "default_timestamp_header")]
+    pub timestamp_header: String,
+    #[serde(default)]
+    pub encoding: SignatureEncoding,
+}
+
+impl HmacSigner {
+    /// Text to sign, as bytes: the body goes in it exactly as it's sent, like a gzip or
+    /// multipart one that is not UTF-8
+    pub fn render(&self, request: &PreparedRequest, timestamp: i64) -> Result<Vec<u8>, String> {
+        let url = Url::parse(&request.url).map_err(|e| e.to_string())?;
+        let query = url.query().unwrap_or_default();
+        let path = match query.is_empty() {
+            true => url.path().to_string(),
+            false => format!("{}?{}", url.path(), query),
+        };
+
+        let parts: Vec<Vec<u8>> = self
+            .template
+            .split("{body}")
+            .map(|part| {
+                part.replace("{method}", &request.method.to_string())
+                    .replace("{host}", url.host_str().unwrap_or_default())
+                    .replace("{path}", &path)
+                    .replace("{query}", query)
+                    .replace("{timestamp}", &timestamp.to_string())
+                    .into_bytes()
+            })
+            .collect();
+        Ok(parts.join(get_body(request)))
+    }
+}
+
+impl RequestSigner for HmacSigner {
+    fn sign(&self, request: &mut PreparedRequest, now: OffsetDateTime) -> Result<(), String> {
+        let timestamp = now.unix_timestamp();
+        let text = self.render(request, timestamp)?;
+        let mac = hmac_sha256(self.secret.as_bytes(), &text);
+
+        let signature = match self.encoding {
+            SignatureEncoding::Hex => to_hex(&mac),
+            SignatureEncoding::Base64 => base64::engine::general_purpose::STANDARD.encode(mac),
+        };
+
+        if !self.timestamp_header.is_empty() {
+            set_header(request, &self.timestamp_header, timestamp.to_string());
+        }
+        set_header(
+            request,
+            &self.header,
+            format!("{}{}", self.prefix, signature),
+        );
+        Ok(())
+    }
+}
+
+// Signature of a Request, written in the editor as { "type": "hmac", ... }. It's apart
+// of the Auth, so a request may send a token and be signed too
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Signer {
+    #[serde(rename = "aws_sigv4")]
+    AwsSigV4(AwsSigV4),
+    Hmac(HmacSigner),
+}
+
+impl Signer {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::AwsSigV4(_) => "AWS SigV4",
+            Self::Hmac(_) => "HMAC",
+        }
+    }
+}
+
+impl RequestSigner for Signer {
+    fn sign(&self, request: &mut PreparedRequest, now: OffsetDateTime) -> Result<(), String> {
+        match self {
+            Self::AwsSigV4(signer) => signer.sign(request, now),
+            Self::Hmac(signer) => signer.sign(request, now),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::web::request::{Request, METHODS};
+    use std::collections::HashMap;
+
+    fn prepared_request(method: METHODS, url: &str) -> PreparedRequest {
+        let mut request = Request::default();
+        request.set_method(method);
+        request.set_url(url.to_string());
+        request.set_headers(HashMap::new());
+        request.set_body(String::new());
+        PreparedRequest::from(request)
+    }
+
+    // Credentials and date of the test suite of AWS
+    fn aws_signer() -> AwsSigV4 {
+        AwsSigV4 {
+            access_key: String::from("AKIDEXAMPLE"),
+            secret_key: String::from("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY"),
+            session_token: None,
+            region: String::from("us-east-1"),
+            service: String::from("service"),
+        }
+    }
+
+    fn aws_date() -> OffsetDateTime {
+        OffsetDateTime::from_unix_timestamp(1440938160).unwrap()
+    }
+
+    #[test]
+    fn should_sign_as_aws_test_suite() {
+        let mut request = prepared_request(METHODS::GET, "https://example.amazonaws.com/");
+        aws_signer().sign(&mut request, aws_date()).unwrap();
+
+        assert_eq!(request.headers["x-amz-date"], "20150830T123600Z");
+        assert_eq!(
+            request.headers["Authorization"],
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=host;x-amz-date, \
+             Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+
+        let mut request = prepared_request(
+            METHODS::GET,
+            "https://example.amazonaws.com/?Param2=value2&Param1=value1",
+        );
+        aws_signer().sign(&mut request, aws_date()).unwrap();
+        assert!(request.headers["Authorization"].ends_with(
+            "Signature=b97d918cfa904a5beff61c982a1b6f458b799221646efd99d3219ec94cdf2500"
+        ));
+    }
+
+    #[test]
+    fn should_encode_path_once_only_for_s3() {
+        let url = Url::parse("https://example.com/a%20b/c").unwrap();
+
+        assert_eq!(aws_signer().get_canonical_uri(&url), "/a%2520b/c");
+        let s3 = AwsSigV4 {
+            service: String::from("s3"),
+            ..aws_signer()
+        };
+        assert_eq!(s3.get_canonical_uri(&url), "/a%20b/c");
+    }
+
+    #[test]
+    fn should_sign_with_hmac_template() {
+        let signer: HmacSigner =
+            serde_json::from_str(r#"{ "secret": "key", "prefix": "v1=" }"#).unwrap();
+        let mut request = prepared_request(METHODS::POST, "https://api.partner.com/orders?id=1");
+        request.body = String::from(r#"{"qty":2}"#);
+
+        assert_eq!(
+            signer.render(&request, 1700000000).unwrap(),
+            b"POST\n/orders?id=1\n1700000000\n{\"qty\":2}"
+        );
+
+        let now = OffsetDateTime::from_unix_timestamp(1700000000).unwrap();
+        signer.sign(&mut request, now).unwrap();
+
+        let expected = to_hex(&hmac_sha256(
+            b"key",
+            b"POST\n/orders?id=1\n1700000000\n{\"qty\":2}",
+        ));
+        assert_eq!(request.headers["X-Timestamp"], "1700000000");
+        assert_eq!(request.headers["X-Signature"], format!("v1={}", expected));
+    }
+
+    #[test]
+    fn should_sign_raw_body_as_it_is_sent() {
+        let signer: HmacSigner =
+            serde_json::from_str(r#"{ "secret": "key", "template": "{method} {body}" }"#).unwrap();
+        let mut request = prepared_request(METHODS::POST, "https://api.partner.com/orders");
+        request.raw_body = Some(vec![0x1f, 0x8b, 0xff, 0x00]);
+
+        assert_eq!(
+            signer.render(&request, 1700000000).unwrap(),
+            b"POST \x1f\x8b\xff\x00"
+        );
+    }
+
+    // Example of RFC 4231, test case 2
+    #[test]
+    fn should_compute_hmac_sha256() {
+        assert_eq!(
+            to_hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
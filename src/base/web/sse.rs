@@ -0,0 +1,187 @@
+use std::time::SystemTime;
+
+use crate::utils::time::format_utc_time;
+
+pub static EVENT_STREAM_CONTENT_TYPE: &str = "text/event-stream";
+static DEFAULT_EVENT_NAME: &str = "message";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ServerSentEvent {
+    pub event: String,
+    pub id: Option<String>,
+    pub data: String,
+    pub retry: Option<u64>,
+    pub received_at: SystemTime,
+}
+
+impl ServerSentEvent {
+    /// Time the event was received, in UTC
+    pub fn get_time_as_text(&self) -> String {
+        format_utc_time(self.received_at)
+    }
+}
+
+// Parser of a 'text/event-stream' body that receives it by chunks, following the
+// HTML specification (lines ended by LF, CRLF or a bare CR)
+#[derive(Default)]
+pub struct SseParser {
+    buffer: Vec<u8>,
+    // A line ended by CR, whose LF may come at the start of the next chunk
+    after_cr: bool,
+    event: Option<String>,
+    data: Vec<String>,
+    retry: Option<u64>,
+    // The last id is kept to the next events, as the spec says
+    last_id: Option<String>,
+}
+
+impl SseParser {
+    /// Events completed with the chunk received
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<ServerSentEvent> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = vec![];
+
+        loop {
+            if self.after_cr && !self.buffer.is_empty() {
+                self.after_cr = false;
+                if self.buffer[0] == b'\n' {
+                    self.buffer.remove(0);
+                }
+            }
+
+            let end = match self.buffer.iter().position(|b| *b == b'\n' || *b == b'\r') {
+                Some(end) => end,
+                None => break,
+            };
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            self.after_cr = line[end] == b'\r';
+            let line = String::from_utf8_lossy(&line[..end]);
+
+            if let Some(event) = self.process_line(&line) {
+                events.push(event);
+            }
+        }
+
+        events
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<ServerSentEvent> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+
+        // Lines starting with colon are comments
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            "id" if !value.contains('\0') => self.last_id = Some(value.to_string()),
+            "retry" => self.retry = value.parse().ok().or(self.retry),
+            _ => {}
+        }
+
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<ServerSentEvent> {
+        let event = self.event.take();
+        let retry = self.retry.take();
+
+        if self.data.is_empty() {
+            return None;
+        }
+
+        Some(ServerSentEvent {
+            event: event
+                .filter(|e| !e.is_empty())
+                .unwrap_or_else(|| String::from(DEFAULT_EVENT_NAME)),
+            id: self.last_id.clone(),
+            data: std::mem::take(&mut self.data).join("\n"),
+            retry,
+            received_at: SystemTime::now(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(events: &[ServerSentEvent]) -> Vec<(String, Option<String>, String)> {
+        events
+            .iter()
+            .map(|e| (e.event.clone(), e.id.clone(), e.data.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn should_end_lines_with_bare_cr() {
+        let mut parser = SseParser::default();
+
+        let first = parser.feed(b"event: a\rdata: 1\r\r");
+        let second = parser.feed(b"data: 2\r");
+        let third = parser.feed(b"\ndata: 3\r\n\r");
+
+        assert_eq!(
+            summary(&first),
+            vec![("a".to_string(), None, "1".to_string())]
+        );
+        assert!(second.is_empty());
+        assert_eq!(
+            summary(&third),
+            vec![("message".to_string(), None, "2\n3".to_string())]
+        );
+    }
+
+    #[test]
+    fn should_parse_events_split_in_chunks() {
+        let mut parser = SseParser::default();
+
+        let first = parser.feed(b": connected\n\nid: 1\nevent: update\ndata: {\"a\"");
+        let second = parser.feed(b": 1}\r\n\r\ndata: first line\ndata: second line\n\n");
+
+        assert!(first.is_empty());
+        assert_eq!(
+            summary(&second),
+            vec![
+                ("update".to_string(), Some("1".to_string()), "{\"a\": 1}".to_string()),
+                (
+                    "message".to_string(),
+                    Some("1".to_string()),
+                    "first line\nsecond line".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_ignore_events_without_data() {
+        let mut parser = SseParser::default();
+
+        let events = parser.feed(b"event: ping\n\nretry: 3000\ndata\n\n");
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event, "message");
+        assert_eq!(events[0].data, "");
+        assert_eq!(events[0].retry, Some(3000));
+    }
+
+    #[test]
+    fn should_keep_utf8_split_between_chunks() {
+        let mut parser = SseParser::default();
+        let data = "data: olá\n\n".as_bytes();
+
+        let mut events = parser.feed(&data[..8]);
+        events.extend(parser.feed(&data[8..]));
+
+        assert_eq!(events[0].data, "olá");
+    }
+}
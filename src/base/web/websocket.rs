@@ -0,0 +1,236 @@
+use std::time::SystemTime;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::utils::time::format_utc_time;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameDirection {
+    Sent,
+    Received,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameKind {
+    Text,
+    Binary,
+    Ping,
+    Pong,
+    Close,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebSocketFrame {
+    pub direction: FrameDirection,
+    pub kind: FrameKind,
+    pub data: Vec<u8>,
+    pub at: SystemTime,
+}
+
+impl WebSocketFrame {
+    pub fn init(direction: FrameDirection, kind: FrameKind, data: Vec<u8>) -> Self {
+        Self {
+            direction,
+            kind,
+            data,
+            at: SystemTime::now(),
+        }
+    }
+
+    /// Time the frame was sent or received, in UTC
+    pub fn get_time_as_text(&self) -> String {
+        format_utc_time(self.at)
+    }
+
+    // Text and reason of Close are shown as they are, any other data in hex
+    pub fn get_data_as_text(&self) -> String {
+        match self.kind {
+            FrameKind::Text | FrameKind::Close => String::from_utf8_lossy(&self.data).to_string(),
+            _ => self
+                .data
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<Vec<String>>()
+                .join(" "),
+        }
+    }
+
+    pub fn to_line(&self) -> String {
+        let arrow = match self.direction {
+            FrameDirection::Sent => ">>",
+            FrameDirection::Received => "<<",
+        };
+        let kind = match self.kind {
+            FrameKind::Text => "TEXT",
+            FrameKind::Binary => "BINARY",
+            FrameKind::Ping => "PING",
+            FrameKind::Pong => "PONG",
+            FrameKind::Close => "CLOSE",
+        };
+
+        format!(
+            "[{}] {} {} {}",
+            self.get_time_as_text(),
+            arrow,
+            kind,
+            self.get_data_as_text()
+        )
+    }
+}
+
+// Message to be sent by an open connection
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WebSocketMessage {
+    Text(String),
+    Binary(Vec<u8>),
+    Close,
+}
+
+impl WebSocketMessage {
+    /// Message with the body of a Request. Binary ones are written in hex, like "ca fe 01"
+    pub fn from_body(body: &str, binary: bool) -> Result<Self, String> {
+        if !binary {
+            return Ok(Self::Text(body.to_string()));
+        }
+
+        let hex: String = body.chars().filter(|c| !c.is_whitespace()).collect();
+        if let Some(c) = hex.chars().find(|c| !c.is_ascii_hexdigit()) {
+            return Err(format!("Binary body is not hex: '{}' is not a hex digit", c));
+        }
+        if !hex.len().is_multiple_of(2) {
+            return Err(String::from("Binary body should have two hex digits by byte"));
+        }
+
+        // Only ASCII digits are left, so each one is a single byte
+        let bytes = hex
+            .as_bytes()
+            .chunks(2)
+            .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap_or_default(), 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|e| format!("Binary body is not hex: {}", e))?;
+
+        Ok(Self::Binary(bytes))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WebSocketEvent {
+    Opened,
+    Frame(WebSocketFrame),
+    // With the error, when it was not closed by one of the sides
+    Closed(Option<String>),
+}
+
+// Called by the connection with everything that happens with it, from its own task
+pub type WebSocketListener = dyn Fn(WebSocketEvent) + Send + Sync;
+
+// Handle to send messages to a connection. It is closed when all of them are dropped
+#[derive(Clone, Debug)]
+pub struct WebSocketConnection {
+    sender: UnboundedSender<WebSocketMessage>,
+}
+
+impl WebSocketConnection {
+    pub fn init(sender: UnboundedSender<WebSocketMessage>) -> Self {
+        Self { sender }
+    }
+
+    pub fn is_open(&self) -> bool {
+        !self.sender.is_closed()
+    }
+
+    pub fn send(&self, message: WebSocketMessage) -> Result<(), String> {
+        self.sender
+            .send(message)
+            .map_err(|_| String::from("Connection is closed"))
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum WebSocketStatus {
+    #[default]
+    Disconnected,
+    Connecting,
+    Open,
+    Closed(Option<String>),
+}
+
+// What is shown of a WebSocket tab
+#[derive(Clone, Debug, Default)]
+pub struct WebSocketTranscript {
+    pub status: WebSocketStatus,
+    pub frames: Vec<WebSocketFrame>,
+}
+
+impl WebSocketTranscript {
+    pub fn start_connecting(&mut self) {
+        self.status = WebSocketStatus::Connecting;
+        self.frames.clear();
+    }
+
+    pub fn apply(&mut self, event: WebSocketEvent) {
+        match event {
+            WebSocketEvent::Opened => self.status = WebSocketStatus::Open,
+            WebSocketEvent::Frame(frame) => self.frames.push(frame),
+            WebSocketEvent::Closed(error) => self.status = WebSocketStatus::Closed(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_create_message_from_body() {
+        assert_eq!(
+            WebSocketMessage::from_body("{\"a\": 1}", false),
+            Ok(WebSocketMessage::Text("{\"a\": 1}".to_string()))
+        );
+        assert_eq!(
+            WebSocketMessage::from_body("ca fe\n01", true),
+            Ok(WebSocketMessage::Binary(vec![0xca, 0xfe, 0x01]))
+        );
+        assert!(WebSocketMessage::from_body("caf", true).is_err());
+        assert!(WebSocketMessage::from_body("zz", true).is_err());
+        assert!(WebSocketMessage::from_body("aéb", true).is_err());
+        assert!(WebSocketMessage::from_body("caé", true).is_err());
+    }
+
+    #[test]
+    fn should_show_frame_data() {
+        let text = WebSocketFrame::init(FrameDirection::Sent, FrameKind::Text, b"hi".to_vec());
+        let binary =
+            WebSocketFrame::init(FrameDirection::Received, FrameKind::Binary, vec![0, 255]);
+
+        assert!(text.to_line().ends_with(">> TEXT hi"));
+        assert!(binary.to_line().ends_with("<< BINARY 00 ff"));
+    }
+
+    #[test]
+    fn should_update_transcript_with_events() {
+        let mut transcript = WebSocketTranscript::default();
+        transcript.frames.push(WebSocketFrame::init(
+            FrameDirection::Sent,
+            FrameKind::Text,
+            vec![],
+        ));
+
+        transcript.start_connecting();
+        assert_eq!(transcript.status, WebSocketStatus::Connecting);
+        assert!(transcript.frames.is_empty());
+
+        transcript.apply(WebSocketEvent::Opened);
+        transcript.apply(WebSocketEvent::Frame(WebSocketFrame::init(
+            FrameDirection::Received,
+            FrameKind::Text,
+            b"welcome".to_vec(),
+        )));
+        transcript.apply(WebSocketEvent::Closed(Some("reset".to_string())));
+
+        assert_eq!(transcript.frames.len(), 1);
+        assert_eq!(
+            transcript.status,
+            WebSocketStatus::Closed(Some("reset".to_string()))
+        );
+    }
+}
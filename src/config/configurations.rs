@@ -1,6 +1,7 @@
 pub mod external_editor;
 pub mod save_files;
 pub mod view;
+pub mod web_client;
 
 use std::collections::HashMap;
 
@@ -0,0 +1,71 @@
+use crate::utils::file_facades::config_file::ConfigFile;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+// Settings of the HTTP client shared by all requests. Read from [client] table of config.toml
+//
+// [client]
+// pool_max_idle_per_host = 10
+// pool_idle_timeout = 90        # seconds
+// tcp_keepalive = 60            # seconds
+// http2_prior_knowledge = false
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebClientConfig {
+    pub pool_max_idle_per_host: Option<usize>,
+    pub pool_idle_timeout: Option<u64>,
+    pub tcp_keepalive: Option<u64>,
+    pub http2_prior_knowledge: bool,
+}
+impl WebClientConfig {
+    pub fn setup_and_init() -> Result<Self, String> {
+        ConfigFile::main().get_section("client")
+    }
+
+    pub fn get_pool_idle_timeout(&self) -> Option<Duration> {
+        self.pool_idle_timeout.map(Duration::from_secs)
+    }
+
+    pub fn get_tcp_keepalive(&self) -> Option<Duration> {
+        self.tcp_keepalive.map(Duration::from_secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_client_section() {
+        let content = r#"
+            [client]
+            pool_max_idle_per_host = 4
+            pool_idle_timeout = 30
+            http2_prior_knowledge = true
+        "#;
+
+        let config: WebClientConfig = ConfigFile::parse_section(content, "client").unwrap();
+
+        assert_eq!(config.pool_max_idle_per_host, Some(4));
+        assert_eq!(config.get_pool_idle_timeout(), Some(Duration::from_secs(30)));
+        assert_eq!(config.get_tcp_keepalive(), None);
+        assert!(config.http2_prior_knowledge);
+    }
+
+    #[test]
+    fn should_use_default_if_section_is_missing() {
+        let config: WebClientConfig = ConfigFile::parse_section("", "client").unwrap();
+        assert_eq!(config, WebClientConfig::default());
+    }
+
+    #[test]
+    fn should_fail_with_invalid_values() {
+        let content = r#"
+            [client]
+            pool_idle_timeout = "thirty"
+        "#;
+
+        let config: Result<WebClientConfig, String> = ConfigFile::parse_section(content, "client");
+        assert!(config.is_err());
+    }
+}
@@ -1,17 +1,31 @@
 use super::configurations::external_editor::ExternalEditor;
 use super::configurations::view::ViewConfig;
+use super::configurations::web_client::WebClientConfig;
 use super::configurations::Configuration;
 use crate::base::os::file_edition_handler::FileEditionHandler;
+use crate::base::web::auth::Auth;
+use crate::base::web::cookies::CookieJar;
+use crate::base::web::oauth::TokenCache;
+use crate::base::web::options::RequestOptions;
+use crate::base::web::signing::Signer;
 use crate::config::configurations::save_files::SaveFiles;
-use crate::utils::file_facades::{data_file::DataFile, request_file::RequestFile, FileFacade};
+use crate::utils::file_facades::{
+    config_file::ConfigFile, data_file::DataFile, request_file::RequestFile, FileFacade,
+};
 use std::rc::Rc;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
 #[derive(Clone)]
 pub struct ConfigManager {
     pub saved_requests: Rc<Mutex<SaveFiles>>,
     pub editor: Rc<ExternalEditor>,
     pub view: Rc<Mutex<ViewConfig>>,
+    pub web_client: Rc<WebClientConfig>,
+    pub request_defaults: Rc<RequestOptions>,
+    pub default_auth: Rc<Auth>,
+    pub default_signer: Rc<Option<Signer>>,
+    pub cookies: Arc<CookieJar>,
+    pub oauth_tokens: Arc<TokenCache>,
     pub edition_files_handler: Rc<Mutex<FileEditionHandler>>,
 }
 impl ConfigManager {
@@ -19,10 +33,29 @@ impl ConfigManager {
         let saved_requests = SaveFiles::setup_and_init().unwrap();
         let editor = ExternalEditor::setup_and_init().unwrap();
         let view = ViewConfig::init();
+        let web_client = WebClientConfig::setup_and_init().expect("Invalid config.toml");
+        let request_defaults = ConfigFile::main()
+            .get_section("request")
+            .expect("Invalid config.toml");
+        let default_auth = ConfigFile::main()
+            .get_section("auth")
+            .expect("Invalid config.toml");
+        let default_signer = ConfigFile::main()
+            .get_section("signer")
+            .expect("Invalid config.toml");
+        let cookies = CookieJar::setup_and_init().expect("Invalid cookies.json in data folder");
+        let oauth_tokens =
+            TokenCache::setup_and_init().expect("Invalid oauth_tokens.json in data folder");
         Self {
             saved_requests: Rc::new(Mutex::new(saved_requests)),
             view: Rc::new(Mutex::new(view)),
             editor: Rc::new(editor),
+            web_client: Rc::new(web_client),
+            request_defaults: Rc::new(request_defaults),
+            default_auth: Rc::new(default_auth),
+            default_signer: Rc::new(default_signer),
+            cookies: Arc::new(cookies),
+            oauth_tokens: Arc::new(oauth_tokens),
             edition_files_handler: Rc::new(Mutex::new(FileEditionHandler::default())),
         }
     }
@@ -30,6 +63,7 @@ impl ConfigManager {
     pub fn setup_env() -> Result<(), String> {
         RequestFile::setup()?;
         DataFile::setup()?;
+        ConfigFile::setup()?;
         Ok(())
     }
 }
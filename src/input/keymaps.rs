@@ -29,6 +29,13 @@ pub fn default_keymap_factory() -> KeyMap {
                 sub_action: None,
             },
         ),
+        (
+            KeyCode::Esc,
+            Actionable {
+                action: Actions::Cancel,
+                sub_action: None,
+            },
+        ),
         (
             KeyCode::Char('q'),
             Actionable {
@@ -170,6 +177,48 @@ pub fn default_keymap_factory() -> KeyMap {
                 sub_action: None,
             },
         ),
+        (
+            KeyCode::Char('w'),
+            Actionable {
+                action: Actions::NewWebSocket,
+                sub_action: None,
+            },
+        ),
+        (
+            KeyCode::Char('Q'),
+            Actionable {
+                action: Actions::NewGraphQL,
+                sub_action: None,
+            },
+        ),
+        (
+            KeyCode::Char('P'),
+            Actionable {
+                action: Actions::NewGrpc,
+                sub_action: None,
+            },
+        ),
+        (
+            KeyCode::Char('i'),
+            Actionable {
+                action: Actions::Introspect,
+                sub_action: None,
+            },
+        ),
+        (
+            KeyCode::Char('o'),
+            Actionable {
+                action: Actions::SwitchOperation,
+                sub_action: None,
+            },
+        ),
+        (
+            KeyCode::Char('b'),
+            Actionable {
+                action: Actions::SwitchBodyMode,
+                sub_action: None,
+            },
+        ),
         (
             KeyCode::Char('s'),
             Actionable {
@@ -177,6 +226,13 @@ pub fn default_keymap_factory() -> KeyMap {
                 sub_action: None,
             },
         ),
+        (
+            KeyCode::Char('m'),
+            Actionable {
+                action: Actions::MethodEdit,
+                sub_action: None,
+            },
+        ),
         (
             KeyCode::Char('r'),
             Actionable {
@@ -6,6 +6,8 @@ use base::commands::handler::CommandHandler;
 use base::stores::MainStore;
 use base::web::client::WebClient;
 use base::web::repository::reqwest::ReqwestClientRepository;
+use base::web::repository::tonic::TonicClientRepository;
+use base::web::repository::tungstenite::TungsteniteClientRepository;
 use commands::Commands;
 use config::manager::ConfigManager;
 use std::error::Error;
@@ -47,14 +49,20 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let config_manager = ConfigManager::init();
     // let request_store = RequestStore::init(config_manager.saved_requests);
 
+    // Init Web Client
+    let web_client: WebClient<ReqwestClientRepository> = WebClient::init(
+        ReqwestClientRepository::init(&config_manager.web_client, config_manager.cookies.clone())
+            .expect("Error creating HTTP client with [client] of config.toml"),
+        (*config_manager.request_defaults).clone(),
+        (*config_manager.default_auth).clone(),
+        config_manager.oauth_tokens.clone(),
+    )
+    .with_default_signer((*config_manager.default_signer).clone());
+
     // Init of Data Stores
     let mut data_store = MainStore::init(config_manager);
     data_store.set_log_warning(String::from("NEEDING HELP,"), String::from("press [?]"));
 
-    // Init Web Client
-    let web_client: WebClient<ReqwestClientRepository> =
-        WebClient::init(ReqwestClientRepository::default());
-
     // User Input
     let (action_queue_sender, action_queue_receiver): (Sender<Actions>, Receiver<Actions>) =
         mpsc::channel();
@@ -76,6 +84,8 @@ async fn main() -> Result<(), Box<dyn Error>> {
     app.set_action_manager(action_manager);
     app.set_command_handler(command_handler);
     app.set_web_client(web_client);
+    app.set_socket_client(TungsteniteClientRepository::default());
+    app.set_grpc_client(TonicClientRepository::default());
     app.set_data_store(data_store);
     app.set_renderer(action_queue_sender.clone());
 
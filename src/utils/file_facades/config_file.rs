@@ -0,0 +1,52 @@
+use super::{FileFacade, FileUtils};
+use serde::de::DeserializeOwned;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug)]
+pub struct ConfigFile {
+    path: PathBuf,
+}
+impl ConfigFile {
+    pub fn main() -> Self {
+        Self::from_name(String::from("config.toml"))
+    }
+
+    /// Read a table of the TOML file as a configuration struct. If file or table
+    /// do not exist, it returns the default value of configuration
+    pub fn get_section<T: DeserializeOwned + Default>(&self, section: &str) -> Result<T, String> {
+        let content = match self.get_content() {
+            Ok(content) => content,
+            Err(_) => return Ok(T::default()),
+        };
+
+        Self::parse_section(&content, section)
+    }
+
+    pub fn parse_section<T: DeserializeOwned + Default>(
+        content: &str,
+        section: &str,
+    ) -> Result<T, String> {
+        let file: toml::Value = toml::from_str(content).map_err(|e| e.to_string())?;
+
+        match file.get(section) {
+            Some(value) => value
+                .clone()
+                .try_into()
+                .map_err(|e| format!("[{}] {}", section, e)),
+            None => Ok(T::default()),
+        }
+    }
+}
+impl FileFacade for ConfigFile {
+    fn get_path(&self) -> PathBuf {
+        self.path.clone()
+    }
+    fn get_parent_path() -> PathBuf {
+        FileUtils::get_config_dir().unwrap()
+    }
+
+    fn from_name(filename: String) -> Self {
+        let path = Self::get_parent_path().join(filename);
+        Self { path }
+    }
+}
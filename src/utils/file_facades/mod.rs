@@ -1,3 +1,4 @@
+pub mod config_file;
 pub mod data_file;
 pub mod request_file;
 pub mod edition_file;
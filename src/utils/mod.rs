@@ -1,3 +1,4 @@
 pub mod custom_types;
 pub mod file_facades;
 pub mod files;
+pub mod time;
@@ -0,0 +1,26 @@
+use cookie::time::OffsetDateTime;
+use std::time::SystemTime;
+
+/// Time of the day of `at` in UTC, with milliseconds, like "13:05:42.120"
+pub fn format_utc_time(at: SystemTime) -> String {
+    let time = OffsetDateTime::from(at);
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        time.hour(),
+        time.minute(),
+        time.second(),
+        time.millisecond()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn should_format_time_of_day() {
+        let at = SystemTime::UNIX_EPOCH + Duration::from_millis(86_400_000 + 47_142_120);
+        assert_eq!(format_utc_time(at), "13:05:42.120");
+    }
+}
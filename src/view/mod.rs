@@ -18,7 +18,17 @@ mod drawers {
         widgets::{Clear, Wrap},
     };
 
-    use crate::base::{logs::LogType, web::request::METHODS};
+    use crate::base::{
+        logs::LogType,
+        web::auth::Auth,
+        web::form,
+        web::grpc::{GrpcStatus, GrpcTarget},
+        web::multipart,
+        web::query,
+        web::request::{BodyMode, RequestKind, METHODS},
+        web::response::{Response, ResponseStage},
+        web::websocket::WebSocketStatus,
+    };
 
     use super::*;
     pub fn draw_tablist_requests<T>(frame: &mut Frame<T>, area: Rect, store: &MainStore) -> ()
@@ -80,9 +90,10 @@ mod drawers {
             })
             .title("Logs");
 
-        let type_log = store.log.log_type;
-        let title_log = &store.log.title;
-        let details_log = store.log.detail.as_deref().unwrap_or("");
+        let log = store.get_log();
+        let type_log = log.log_type;
+        let title_log = &log.title;
+        let details_log = log.detail.as_deref().unwrap_or("");
 
         let style_by_type = |t: LogType| match t {
             LogType::Error => Style::default().fg(Color::Red),
@@ -111,41 +122,131 @@ mod drawers {
         frame.render_widget(log_command_queue, area);
     }
 
+    // Title of a block with many sections, like "BODY / Headers" when Body is the active one
+    fn sections_title(sections: &[&str], active: usize) -> Vec<Span<'static>> {
+        let mut spans = vec![];
+        for (i, section) in sections.iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::from(" / "));
+            }
+            if i == active {
+                spans.push(Span::styled(
+                    section.to_uppercase(),
+                    Style::default().fg(Color::LightYellow),
+                ));
+            } else {
+                spans.push(Span::from(section.to_string()));
+            }
+        }
+        spans
+    }
+
     pub fn draw_body_request_section<T>(frame: &mut Frame<T>, area: Rect, store: &MainStore) -> ()
     where
         T: Backend,
     {
-        let title_in_body = vec![
-            Span::styled("BODY", Style::default().fg(Color::LightYellow)),
-            Span::from(" / Headers"),
-        ];
-        let title_in_header = vec![
-            Span::from("Body / "),
-            Span::styled("HEADERS", Style::default().fg(Color::LightYellow)),
-        ];
+        let request = store.get_request();
+        let is_graphql = request.kind == RequestKind::GraphQL;
+
+        // The body of GraphQL requests is its query, sent with the chosen operation
+        let query_section = match &request.graphql.operation_name {
+            Some(operation_name) => format!("Query ({})", operation_name),
+            None => String::from("Query"),
+        };
+        // Bodies built from parts show them, instead of the raw text
+        let (body_section, body_content) = match (request.kind, request.body_mode) {
+            (RequestKind::Http, BodyMode::Multipart) => (
+                format!("Body ({})", request.body_mode.get_name()),
+                multipart::parts_as_table(&request.multipart),
+            ),
+            (RequestKind::Http, BodyMode::Form) => (
+                format!("Body ({})", request.body_mode.get_name()),
+                form::fields_as_table(&request.form),
+            ),
+            _ => (String::from("Body"), request.body.clone()),
+        };
+        let params = request.get_params();
+        let params_section = match params.len() {
+            0 => String::from("Params"),
+            n => format!("Params ({})", n),
+        };
+
+        // Inherited credentials are named after the ones of config.toml
+        let has_auth = matches!(request.kind, RequestKind::Http | RequestKind::GraphQL);
+        let auth_section = match &request.auth {
+            Auth::Inherit => match &*store.config.default_auth {
+                Auth::Inherit | Auth::None => String::from("Auth"),
+                default => format!("Auth (inherited {})", default.get_name()),
+            },
+            Auth::None => String::from("Auth"),
+            auth => format!("Auth ({})", auth.get_name()),
+        };
+        let signer_section = match (&request.signer, &*store.config.default_signer) {
+            (Some(signer), _) => format!("Signer ({})", signer.get_name()),
+            (None, Some(default)) => format!("Signer (inherited {})", default.get_name()),
+            (None, None) => String::from("Signer"),
+        };
+
+        // Each section with the state showing it, gRPC calls have no query params and
+        // only HTTP and GraphQL requests send credentials
+        let mut sections = vec![];
+        if is_graphql {
+            sections.push((StatesNames::RequestBody, query_section.as_str()));
+            sections.push((StatesNames::RequestVariables, "Variables"));
+        } else {
+            sections.push((StatesNames::RequestBody, body_section.as_str()));
+        }
+        if request.kind != RequestKind::Grpc {
+            sections.push((StatesNames::RequestParams, params_section.as_str()));
+        }
+        sections.push((StatesNames::RequestHeaders, "Headers"));
+        if has_auth {
+            sections.push((StatesNames::RequestAuth, auth_section.as_str()));
+            sections.push((StatesNames::RequestSigner, signer_section.as_str()));
+        }
+        sections.push((StatesNames::RequestOptions, "Options"));
+
+        let active_section = sections
+            .iter()
+            .position(|(state, _)| *state == store.current_state)
+            .unwrap_or(0);
+        let sections: Vec<&str> = sections.iter().map(|(_, title)| *title).collect();
 
         let body_block = Block::default()
             .borders(Borders::ALL)
-            .title(match store.current_state {
-                StatesNames::RequestHeaders => title_in_header,
-                _ => title_in_body,
-            })
+            .title(sections_title(&sections, active_section))
             .title_alignment(Alignment::Left)
             //
             .style(match store.current_state {
-                StatesNames::RequestHeaders | StatesNames::RequestBody => {
-                    Style::default().fg(Color::LightYellow)
-                }
+                StatesNames::RequestHeaders
+                | StatesNames::RequestBody
+                | StatesNames::RequestVariables
+                | StatesNames::RequestParams
+                | StatesNames::RequestAuth
+                | StatesNames::RequestSigner
+                | StatesNames::RequestOptions => Style::default().fg(Color::LightYellow),
                 _ => Style::default(),
             })
             .border_type(BorderType::Rounded);
 
         let content = match store.current_state {
-            StatesNames::RequestBody => store.get_request().body.clone(),
+            StatesNames::RequestBody => body_content,
+            StatesNames::RequestVariables if is_graphql => request.graphql.variables.clone(),
+            StatesNames::RequestParams => query::params_as_table(&params),
             StatesNames::RequestHeaders => {
-                serde_json::to_string_pretty(&store.get_request().headers).unwrap_or(String::new())
+                serde_json::to_string_pretty(&request.headers).unwrap_or(String::new())
             }
-            _ => store.get_request().body.clone(),
+            StatesNames::RequestAuth => {
+                serde_json::to_string_pretty(&request.auth).unwrap_or_default()
+            }
+            StatesNames::RequestSigner => match &request.signer {
+                Some(signer) => serde_json::to_string_pretty(signer).unwrap_or_default(),
+                None => String::from("No signer, press [e] to add one"),
+            },
+            StatesNames::RequestOptions => {
+                serde_json::to_string_pretty(&request.options).unwrap_or_default()
+            }
+            _ => body_content,
         };
 
         let body_text = Paragraph::new(content)
@@ -160,27 +261,69 @@ mod drawers {
     where
         T: Backend,
     {
+        let method_name = match store.get_request().kind {
+            RequestKind::WebSocket => String::from("WS"),
+            RequestKind::GraphQL => String::from("GQL"),
+            RequestKind::Grpc => String::from("GRPC"),
+            RequestKind::Http => store.get_request().method.to_string(),
+        };
+        let method_width = std::cmp::max(7, method_name.len() as u16 + 2);
+
         let layout = Layout::default()
             .direction(Direction::Horizontal)
             .margin(0)
-            .constraints([Constraint::Length(7), Constraint::Min(1)].as_ref())
+            .constraints([Constraint::Length(method_width), Constraint::Min(1)].as_ref())
             .split(area);
 
-        let method = Paragraph::new(store.get_request().method.to_string())
-            .style(match store.get_request().method {
+        let method_style = match store.get_request().kind {
+            RequestKind::WebSocket => Style::default().bg(Color::LightGreen).fg(Color::Black),
+            RequestKind::GraphQL => Style::default().bg(Color::LightCyan).fg(Color::Black),
+            RequestKind::Grpc => Style::default().bg(Color::DarkGray).fg(Color::White),
+            RequestKind::Http => match store.get_request().method {
                 METHODS::GET => Style::default().bg(Color::Blue).fg(Color::Black),
                 METHODS::POST => Style::default().bg(Color::Green).fg(Color::Black),
                 METHODS::PUT => Style::default().bg(Color::White).fg(Color::Black),
                 METHODS::PATCH => Style::default().bg(Color::Magenta).fg(Color::Black),
                 METHODS::DELETE => Style::default().bg(Color::Red).fg(Color::Black),
                 METHODS::HEAD => Style::default().bg(Color::Yellow).fg(Color::Black),
-            })
+                METHODS::OPTIONS => Style::default().bg(Color::Cyan).fg(Color::Black),
+                METHODS::TRACE => Style::default().bg(Color::Gray).fg(Color::Black),
+                METHODS::CONNECT => Style::default().bg(Color::LightBlue).fg(Color::Black),
+                METHODS::Custom(_) => Style::default().bg(Color::LightMagenta).fg(Color::Black),
+            },
+        };
+
+        let method = Paragraph::new(method_name)
+            .style(method_style)
             .alignment(Alignment::Center);
         frame.render_widget(method, layout[0]);
 
+        let request = store.get_request();
+        let mut url_details = vec![];
+        if let Some(active_proxy) = store.get_active_proxy() {
+            url_details.push(format!("proxy: {}", active_proxy));
+        }
+        if request.kind == RequestKind::GraphQL {
+            if let Some(schema) = store.graphql_schemas.get_schema(&request.url) {
+                url_details.push(format!("schema: {} types", schema.get_types_count()));
+            }
+        }
+        if request.kind == RequestKind::Grpc {
+            if let Ok(target) = GrpcTarget::parse(&request.url) {
+                let methods = store.grpc_methods.get_methods(&target.endpoint);
+                if !methods.is_empty() {
+                    url_details.push(format!("{} methods", methods.len()));
+                }
+            }
+        }
+        let url_title = match url_details.is_empty() {
+            true => String::from("URL"),
+            false => format!("URL ({})", url_details.join(", ")),
+        };
+
         let url_block = Block::default()
             .borders(Borders::ALL)
-            .title("URL")
+            .title(url_title)
             .title_alignment(Alignment::Left)
             .style(if store.current_state == StatesNames::Url {
                 Style::default().fg(Color::LightYellow)
@@ -196,18 +339,145 @@ mod drawers {
         frame.render_widget(url_text, layout[1]);
     }
 
+    fn cookies_as_text(store: &MainStore) -> String {
+        let cookies = store.config.cookies.get_cookies();
+        if cookies.is_empty() {
+            return String::from("No cookies saved");
+        }
+
+        cookies
+            .iter()
+            .map(|c| {
+                format!(
+                    "{}={}  [{}{}]  expires: {}{}",
+                    c.name,
+                    c.value,
+                    c.domain,
+                    c.path,
+                    c.get_expires_as_text(),
+                    if c.secure { "  (secure)" } else { "" },
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn redirects_as_text(response: &Response) -> String {
+        if response.redirects.is_empty() {
+            return String::from("No redirects");
+        }
+
+        let mut lines: Vec<String> = response
+            .redirects
+            .iter()
+            .map(|hop| {
+                format!(
+                    "{} {} {}  -> {}  ({:.1?})",
+                    hop.status, hop.method, hop.url, hop.location, hop.time
+                )
+            })
+            .collect();
+        lines.push(format!("{} (final)", response.status));
+
+        lines.join("\n")
+    }
+
+    fn events_as_text(response: &Response) -> String {
+        response
+            .events
+            .iter()
+            .map(|event| {
+                let id = match &event.id {
+                    Some(id) => format!("  id: {}", id),
+                    None => String::new(),
+                };
+                format!(
+                    "[{}] {}{}\n{}\n",
+                    event.get_time_as_text(),
+                    event.event,
+                    id,
+                    event.data
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    // Transcript of a WebSocket tab, always scrolled to the last frames
+    pub fn draw_websocket_section<T>(frame: &mut Frame<T>, area: Rect, store: &MainStore) -> ()
+    where
+        T: Backend,
+    {
+        let transcript = store
+            .websockets
+            .get_transcript(store.get_request_uuid());
+
+        let websocket_block = Block::default()
+            .borders(Borders::ALL)
+            .title("WebSocket")
+            .title_alignment(Alignment::Center)
+            .border_type(BorderType::Rounded);
+
+        let websocket_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints([Constraint::Length(1), Constraint::Min(1)].as_ref())
+            .split(area);
+
+        let status = Paragraph::new(match &transcript.status {
+            WebSocketStatus::Disconnected => String::from("Hit ENTER to connect"),
+            WebSocketStatus::Connecting => String::from("Connecting..."),
+            WebSocketStatus::Open => String::from("Open: ENTER sends the Body, [ESC] closes"),
+            WebSocketStatus::Closed(None) => String::from("Closed: ENTER connects again"),
+            WebSocketStatus::Closed(Some(e)) => format!("Closed: {}", e),
+        })
+        .style(match &transcript.status {
+            WebSocketStatus::Disconnected => Style::default().bg(Color::Gray).fg(Color::Black),
+            WebSocketStatus::Connecting => Style::default().bg(Color::Blue).fg(Color::Black),
+            WebSocketStatus::Open => Style::default().bg(Color::Green).fg(Color::Black),
+            WebSocketStatus::Closed(None) => Style::default().bg(Color::Yellow).fg(Color::Black),
+            WebSocketStatus::Closed(Some(_)) => Style::default().bg(Color::Red).fg(Color::Black),
+        })
+        .alignment(Alignment::Center);
+
+        let lines: Vec<String> = transcript.frames.iter().map(|f| f.to_line()).collect();
+        let content = lines.join("\n");
+
+        // Lines that don't fit the block are the oldest ones
+        let height = websocket_layout[1].height.saturating_sub(2);
+        let total_lines = content.lines().count() as u16;
+        let scroll = total_lines.saturating_sub(height);
+
+        let transcript_block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Frames ({})", transcript.frames.len()))
+            .title_alignment(Alignment::Left)
+            .style(match store.current_state {
+                StatesNames::ResponseHeader
+                | StatesNames::ResponseBody
+                | StatesNames::ResponseCookies
+                | StatesNames::ResponseRedirects => Style::default().fg(Color::LightYellow),
+                _ => Style::default(),
+            })
+            .border_type(BorderType::Rounded);
+
+        let transcript_text = Paragraph::new(content)
+            .alignment(Alignment::Left)
+            .scroll((scroll, 0))
+            .block(transcript_block);
+
+        frame.render_widget(websocket_block, area);
+        frame.render_widget(status, websocket_layout[0]);
+        frame.render_widget(transcript_text, websocket_layout[1]);
+    }
+
     pub fn draw_body_response_section<T>(frame: &mut Frame<T>, area: Rect, store: &MainStore) -> ()
     where
         T: Backend,
     {
-        let title_in_body = vec![
-            Span::styled("BODY", Style::default().fg(Color::LightYellow)),
-            Span::from(" / Headers"),
-        ];
-        let title_in_header = vec![
-            Span::from("Body / "),
-            Span::styled("HEADERS", Style::default().fg(Color::LightYellow)),
-        ];
+        if store.get_request().kind == RequestKind::WebSocket {
+            return draw_websocket_section(frame, area, store);
+        }
 
         // RESPONSE SECTION
         let response_block = Block::default()
@@ -224,43 +494,168 @@ mod drawers {
 
         let response = store.get_response().clone();
         let response_data = response.lock().unwrap().clone();
+        let error = match response_data.stage {
+            ResponseStage::Failed => store.get_error().lock().unwrap().clone(),
+            _ => None,
+        };
+
+        let redirects_section = match response_data.redirects.len() {
+            0 => String::from("Redirects"),
+            n => format!("Redirects ({})", n),
+        };
+        let body_section = match &response_data.content_type {
+            Some(content_type) => format!(
+                "Body ({}, {} bytes{})",
+                content_type,
+                response_data.raw_body.len(),
+                if response_data.is_binary() { ", hex" } else { "" }
+            ),
+            None => String::from("Body"),
+        };
+        let sections = [
+            body_section.as_str(),
+            "Headers",
+            "Cookies",
+            redirects_section.as_str(),
+        ];
 
         let status = response_data.status;
+        let stage = response_data.stage;
         let content = match store.current_state {
+            StatesNames::ResponseHeader if !response_data.trailers.is_empty() => format!(
+                "{}\n\nTrailers\n{}",
+                serde_json::to_string_pretty(&response_data.headers).unwrap_or_default(),
+                serde_json::to_string_pretty(&response_data.trailers).unwrap_or_default()
+            ),
             StatesNames::ResponseHeader => {
                 serde_json::to_string_pretty(&response_data.headers).unwrap_or(String::new())
             }
-            _ => response_data.body,
+            StatesNames::ResponseCookies => cookies_as_text(store),
+            StatesNames::ResponseRedirects => redirects_as_text(&response_data),
+            _ if !response_data.events.is_empty() => events_as_text(&response_data),
+            _ => match &response_data.graphql {
+                Some(graphql) => graphql.data.clone(),
+                None => response_data.body.clone(),
+            },
+        };
+
+        // Errors of GraphQL are shown below its data, with the Body
+        let graphql_errors = match (store.current_state, &response_data.graphql) {
+            (
+                StatesNames::ResponseHeader
+                | StatesNames::ResponseCookies
+                | StatesNames::ResponseRedirects,
+                _,
+            ) => vec![],
+            (_, Some(graphql)) => graphql.errors.clone(),
+            (_, None) => vec![],
         };
 
-        let status_code = Paragraph::new(match status {
-            0 => String::from("Hit ENTER to submit"),
-            77 => String::from("Error"), // A STATUS CODE INTERNAL TO INTERNAL ERROR
-            _ => status.to_string(),
+        // gRPC calls have their own status, the one of HTTP is always 200
+        let grpc_status = match stage {
+            ResponseStage::Finished => response_data.grpc_status.clone(),
+            _ => None,
+        };
+
+        let status_code = Paragraph::new(match (stage, status) {
+            (ResponseStage::Empty, _) => String::from("Hit ENTER to submit"),
+            (ResponseStage::Waiting, _) => String::from("Waiting... [ESC] to cancel"),
+            (ResponseStage::Streaming, _) => {
+                format!("Streaming {} events... [ESC] to stop", response_data.events.len())
+            }
+            (ResponseStage::Cancelled, _) if !response_data.events.is_empty() => {
+                String::from("Stream stopped")
+            }
+            (ResponseStage::Cancelled, _) => String::from("Cancelled"),
+            (ResponseStage::Failed, _) => error
+                .as_ref()
+                .map(|e| e.get_title().to_string())
+                .unwrap_or_else(|| String::from("Error")),
+            _ if grpc_status.is_some() => format!(
+                "gRPC {}",
+                grpc_status
+                    .as_ref()
+                    .map(GrpcStatus::get_name)
+                    .unwrap_or_default()
+            ),
+            _ => match &response_data.http_version {
+                Some(version) => format!("{} {}", version, status),
+                None => status.to_string(),
+            },
         })
-        .style(match status {
-            0 => Style::default().bg(Color::Gray).fg(Color::Black),
-            77 => Style::default().bg(Color::Red).fg(Color::Black), // A STATUS CODE INTERNAL TO INTERNAL ERROR
-            100..=199 => Style::default().bg(Color::Gray).fg(Color::Black),
-            200..=299 => Style::default().bg(Color::Green).fg(Color::Black),
-            300..=399 => Style::default().bg(Color::Yellow).fg(Color::Black),
-            400..=499 => Style::default().bg(Color::Magenta).fg(Color::Black),
-            500..=599 => Style::default().bg(Color::LightRed).fg(Color::Black),
+        .style(match (stage, status) {
+            (ResponseStage::Empty, _) => Style::default().bg(Color::Gray).fg(Color::Black),
+            (ResponseStage::Waiting, _) => Style::default().bg(Color::Blue).fg(Color::Black),
+            (ResponseStage::Streaming, _) => Style::default().bg(Color::Cyan).fg(Color::Black),
+            (ResponseStage::Cancelled, _) => Style::default().bg(Color::Yellow).fg(Color::Black),
+            (ResponseStage::Failed, _) => Style::default().bg(Color::Red).fg(Color::Black),
+            _ if grpc_status.as_ref().is_some_and(GrpcStatus::is_ok) => {
+                Style::default().bg(Color::Green).fg(Color::Black)
+            }
+            _ if grpc_status.is_some() => Style::default().bg(Color::Red).fg(Color::Black),
+            (_, 100..=199) => Style::default().bg(Color::Gray).fg(Color::Black),
+            (_, 200..=299) => Style::default().bg(Color::Green).fg(Color::Black),
+            (_, 300..=399) => Style::default().bg(Color::Yellow).fg(Color::Black),
+            (_, 400..=499) => Style::default().bg(Color::Magenta).fg(Color::Black),
+            (_, 500..=599) => Style::default().bg(Color::LightRed).fg(Color::Black),
             _ => Style::default().bg(Color::Cyan).fg(Color::Black),
         })
         .alignment(Alignment::Center);
 
+        let response_time = match stage {
+            ResponseStage::Finished => response_data.response_time.to_summary(),
+            _ => String::new(),
+        };
+
+        // Requests sent without verifying the certificate of the server are always warned
+        let request_options = store
+            .get_request()
+            .options
+            .with_defaults(&store.config.request_defaults);
+        let mut status_spans = vec![];
+        if request_options.is_insecure() {
+            status_spans.push(Span::styled(
+                " TLS NOT VERIFIED ",
+                Style::default().bg(Color::Red).fg(Color::Black),
+            ));
+            status_spans.push(Span::from(" "));
+        }
+        status_spans.push(Span::from(response_time));
+
+        // Compressed bodies show the size they came with and the decoded one
+        if let (ResponseStage::Finished, Some(compression)) = (stage, &response_data.compression) {
+            status_spans.push(Span::from(" "));
+            status_spans.push(Span::styled(
+                format!(" {} ", compression.to_summary()),
+                Style::default().bg(Color::DarkGray).fg(Color::White),
+            ));
+        }
+
+        let response_time = Paragraph::new(Spans::from(status_spans)).alignment(Alignment::Center);
+
+        let status_layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .margin(0)
+            .constraints([Constraint::Percentage(30), Constraint::Percentage(70)].as_ref())
+            .split(response_layout[0]);
+
         let body_response = Block::default()
             .borders(Borders::ALL)
-            .title(match store.current_state {
-                StatesNames::ResponseHeader => title_in_header,
-                _ => title_in_body,
-            })
+            .title(sections_title(
+                &sections,
+                match store.current_state {
+                    StatesNames::ResponseHeader => 1,
+                    StatesNames::ResponseCookies => 2,
+                    StatesNames::ResponseRedirects => 3,
+                    _ => 0,
+                },
+            ))
             .title_alignment(Alignment::Left)
             .style(match store.current_state {
-                StatesNames::ResponseHeader | StatesNames::ResponseBody => {
-                    Style::default().fg(Color::LightYellow)
-                }
+                StatesNames::ResponseHeader
+                | StatesNames::ResponseBody
+                | StatesNames::ResponseCookies
+                | StatesNames::ResponseRedirects => Style::default().fg(Color::LightYellow),
                 _ => Style::default(),
             })
             .border_type(BorderType::Rounded);
@@ -270,8 +665,64 @@ mod drawers {
             .block(body_response.clone());
 
         frame.render_widget(response_block, area);
-        frame.render_widget(status_code, response_layout[0]);
-        frame.render_widget(response_text, response_layout[1]);
+        frame.render_widget(status_code, status_layout[0]);
+        frame.render_widget(response_time, status_layout[1]);
+
+        // Failures have no body, only what went wrong and what may solve it
+        if let Some(error) = error {
+            let error_block = Block::default()
+                .borders(Borders::ALL)
+                .title(error.get_title())
+                .title_alignment(Alignment::Left)
+                .style(Style::default().fg(Color::Red))
+                .border_type(BorderType::Rounded);
+
+            let error_text = Paragraph::new(vec![
+                Spans::from(error.to_string()),
+                Spans::from(""),
+                Spans::from(Span::styled(
+                    format!("Hint: {}", error.get_hint()),
+                    Style::default().fg(Color::Yellow),
+                )),
+            ])
+            .alignment(Alignment::Left)
+            .wrap(Wrap { trim: false })
+            .block(error_block);
+
+            frame.render_widget(error_text, response_layout[1]);
+            return;
+        }
+
+        if graphql_errors.is_empty() {
+            frame.render_widget(response_text, response_layout[1]);
+            return;
+        }
+
+        // At most half of the area, the rest is left to the data
+        let errors_height = std::cmp::min(
+            graphql_errors.len() as u16 + 2,
+            response_layout[1].height / 2,
+        );
+        let body_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(0)
+            .constraints([Constraint::Min(1), Constraint::Length(errors_height)].as_ref())
+            .split(response_layout[1]);
+
+        let errors_block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Errors ({})", graphql_errors.len()))
+            .title_alignment(Alignment::Left)
+            .style(Style::default().fg(Color::Red))
+            .border_type(BorderType::Rounded);
+
+        let errors_text = Paragraph::new(graphql_errors.join("\n"))
+            .alignment(Alignment::Left)
+            .wrap(Wrap { trim: false })
+            .block(errors_block);
+
+        frame.render_widget(response_text, body_layout[0]);
+        frame.render_widget(errors_text, body_layout[1]);
     }
 
     pub fn draw_input_popup<T>(frame: &mut Frame<T>, area: Rect, store: &MainStore) -> ()